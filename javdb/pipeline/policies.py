@@ -2,7 +2,8 @@
 
 from __future__ import annotations
 
-from datetime import datetime, timedelta
+import os
+from datetime import datetime, timedelta, timezone
 from typing import Dict, List
 
 from javdb.spider.contracts import category_to_indicators, is_uncensored_category
@@ -11,6 +12,36 @@ from javdb.spider.magnet_extractor import _parse_size
 
 logger = get_logger(__name__)
 
+try:
+    from javdb.rust_core import parse_history_tz_offset as _rust_parse_tz_offset
+except ImportError:
+    _rust_parse_tz_offset = None
+
+
+def _history_tz() -> timezone:
+    """Timezone named by ``JAVDB_HISTORY_TZ``; UTC when unset or invalid.
+
+    The value is parsed by the Rust history manager so both writers agree.
+    Without the Rust core only the UTC default is available.
+    """
+    value = os.environ.get('JAVDB_HISTORY_TZ', '').strip()
+    if not value:
+        return timezone.utc
+    if _rust_parse_tz_offset is None:
+        logger.warning("JAVDB_HISTORY_TZ needs the Rust core to parse '%s', using UTC", value)
+        return timezone.utc
+    seconds = _rust_parse_tz_offset(value)
+    if seconds is None:
+        logger.warning("Invalid JAVDB_HISTORY_TZ '%s', using UTC", value)
+        return timezone.utc
+    return timezone(timedelta(seconds=seconds))
+
+
+def history_now() -> datetime:
+    """Current time for history timestamps and the recent-visit cutoffs."""
+    return datetime.now(_history_tz())
+
+
 ALIGNMENT_CENSORED_FAMILY = 'censored'
 ALIGNMENT_UNCENSORED_FAMILY = 'uncensored'
 ALIGNMENT_PARSED_FAMILY_CANDIDATES = {
@@ -96,7 +127,7 @@ def should_skip_recent_yesterday_release(href: str, history_data: dict, is_yeste
     visited_str = _get_visited_datetime(history_data[href])
     if not visited_str:
         return False
-    cutoff = (datetime.now() - timedelta(days=1)).strftime('%Y-%m-%d')
+    cutoff = (history_now() - timedelta(days=1)).strftime('%Y-%m-%d')
     return visited_str[:10] >= cutoff
 
 
@@ -109,7 +140,7 @@ def should_skip_recent_today_release(href: str, history_data: dict, is_today_rel
     visited_str = _get_visited_datetime(history_data[href])
     if not visited_str:
         return False
-    cutoff = datetime.now().strftime('%Y-%m-%d')
+    cutoff = history_now().strftime('%Y-%m-%d')
     return visited_str[:10] >= cutoff
 
 
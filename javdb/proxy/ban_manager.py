@@ -2,9 +2,10 @@
 Proxy Ban Manager
 
 Manages proxy ban records in-memory for the current session only.
-When a proxy is banned, it is marked as banned permanently for the
-current session.  On a new session (process restart), all proxies
-start fresh with no bans.
+A ban lasts ``ban_duration_days`` when one is configured (each proxy
+pool bans for its own duration) and otherwise for the rest of the
+session.  On a new session (process restart), all proxies start fresh
+with no bans unless a snapshot is imported.
 
 Prefers the Rust implementation (``javdb_rust_core``) when available,
 falling back to the pure-Python implementation otherwise.
@@ -92,12 +93,15 @@ def _dispatch_remote_unban(proxy_name: str) -> None:
 _global_ban_manager = None
 
 
-def get_ban_manager(**_kwargs):
+def get_ban_manager(ban_duration_days: Optional[int] = None, **_kwargs):
     """Get or create the global ban manager singleton (session-scoped).
 
     Returns the Rust ``RustProxyBanManager`` when available — this is the
     same singleton that every ``RustProxyPool`` uses internally, so ban
-    state stays in sync across all components.
+    state stays in sync across all components.  A ``ban_duration_days``
+    value becomes the singleton's default ban length (the last caller
+    wins); ``None`` leaves it unchanged.  Pools ban for their own
+    ``ban_duration_days`` and only fall back to this default.
     """
     global _global_ban_manager
 
@@ -108,6 +112,8 @@ def get_ban_manager(**_kwargs):
                 "install the wheel (`cd javdb/rust_core && maturin develop --release`) "
                 "or run with --no-proxy"
             )
-        _global_ban_manager = _rust_get_ban_manager()
+        _global_ban_manager = _rust_get_ban_manager(ban_duration_days)
+    elif ban_duration_days is not None:
+        _global_ban_manager.ban_duration_days = ban_duration_days
 
     return _global_ban_manager
@@ -148,6 +148,7 @@ class ProxyInfo:
 
 def create_proxy_pool_from_config(proxy_list_config: List[Dict],
                                    max_failures: int = 3,
+                                   ban_duration_days: Optional[int] = None,
                                    **_kwargs):
     """Create and configure a proxy pool from configuration.
 
@@ -158,6 +159,9 @@ def create_proxy_pool_from_config(proxy_list_config: List[Dict],
     Args:
         proxy_list_config: List of proxy configurations from config.py
         max_failures: Max failures before banning proxy for the session
+        ban_duration_days: Ban length in days for this pool's bans; ``None``
+            uses the shared ban manager's default (session-permanent unless
+            set)
 
     Returns:
         The Rust proxy pool (``RustProxyPool``).
@@ -186,6 +190,7 @@ def create_proxy_pool_from_config(proxy_list_config: List[Dict],
         rust_proxy_list,
         cooldown_seconds=cooldown_seconds,
         max_failures=max_failures,
+        ban_duration_days=ban_duration_days,
     )
     logger.debug("Created Rust proxy pool")
     return pool
@@ -1,5 +1,6 @@
-use chrono::Local;
+use chrono::{DateTime, FixedOffset, Utc};
 use log::{debug, error, info, warn};
+use once_cell::sync::Lazy;
 use pyo3::prelude::*;
 use pyo3::types::{PyAnyMethods, PyDict};
 use std::collections::{HashMap, HashSet};
@@ -7,6 +8,8 @@ use std::fs;
 use std::io::{BufReader, Write};
 use std::path::Path;
 
+use crate::scraper::common::{normalize_video_code, video_codes_equal};
+
 const CSV_HEADER: &[&str] = &[
     "href",
     "phase",
@@ -31,6 +34,106 @@ const TORRENT_CATEGORIES: &[&str] = &[
     "no_subtitle",
 ];
 
+/// Offset used for every history timestamp and for the "today" /
+/// "yesterday" cutoffs compared against them.  ``JAVDB_HISTORY_TZ`` takes
+/// ``UTC`` / ``Z`` or a fixed offset such as ``+08:00``; unset means UTC so
+/// CI runners and developer machines write the same dates.  The Python
+/// history writers resolve the variable through ``parse_history_tz_offset``.
+static HISTORY_TZ: Lazy<FixedOffset> = Lazy::new(|| {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    match std::env::var("JAVDB_HISTORY_TZ") {
+        Ok(v) if !v.trim().is_empty() => parse_tz_offset(&v).unwrap_or_else(|| {
+            warn!("Invalid JAVDB_HISTORY_TZ '{}', using UTC", v);
+            utc
+        }),
+        _ => utc,
+    }
+});
+
+/// Parse ``UTC`` / ``Z`` / ``+HH`` / ``+HH:MM`` / ``-HHMM`` into an offset.
+fn parse_tz_offset(value: &str) -> Option<FixedOffset> {
+    let v = value.trim();
+    if v.eq_ignore_ascii_case("utc") || v.eq_ignore_ascii_case("z") {
+        return FixedOffset::east_opt(0);
+    }
+    let sign = match v.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits: String = v[1..].chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (digits[..2].parse::<i32>().ok()?, digits[2..].parse::<i32>().ok()?),
+        _ => return None,
+    };
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+fn history_now() -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&*HISTORY_TZ)
+}
+
+/// ``(datetime, date)`` strings as written to the history CSV.
+fn format_history_time(now: DateTime<FixedOffset>) -> (String, String) {
+    (
+        now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        now.format("%Y-%m-%d").to_string(),
+    )
+}
+
+/// Which torrent categories supersede which when a record is saved.
+///
+/// Each ``(winner, loser)`` pair clears ``loser`` (and its size) whenever
+/// ``winner`` holds a magnet.  The default reproduces the fixed rules:
+/// ``hacked_subtitle`` beats ``hacked_no_subtitle`` and ``subtitle`` beats
+/// ``no_subtitle``.  ``enabled=False`` keeps every category as saved.
+#[pyclass(name = "RustPriorityPolicy")]
+#[derive(Clone, Debug)]
+pub struct PriorityPolicy {
+    #[pyo3(get, set)]
+    pub enabled: bool,
+    #[pyo3(get, set)]
+    pub pairs: Vec<(String, String)>,
+}
+
+impl Default for PriorityPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pairs: vec![
+                ("hacked_subtitle".into(), "hacked_no_subtitle".into()),
+                ("subtitle".into(), "no_subtitle".into()),
+            ],
+        }
+    }
+}
+
+#[pymethods]
+impl PriorityPolicy {
+    #[new]
+    #[pyo3(signature = (enabled=true, pairs=None))]
+    fn new(enabled: bool, pairs: Option<Vec<(String, String)>>) -> Self {
+        Self {
+            enabled,
+            pairs: pairs.unwrap_or_else(|| Self::default().pairs),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RustPriorityPolicy(enabled={}, pairs={:?})",
+            self.enabled, self.pairs
+        )
+    }
+}
+
 // ── CSV I/O helpers ─────────────────────────────────────────────────────
 
 type Record = HashMap<String, String>;
@@ -62,10 +165,62 @@ fn read_csv_records(path: &str) -> Result<(Vec<String>, Vec<Record>), String> {
     Ok((headers, records))
 }
 
-fn write_csv_records(path: &str, records: &[Record]) -> Result<(), String> {
-    let bom = b"\xef\xbb\xbf";
+/// Stream rows until the first one whose ``key`` column equals ``value``.
+/// Only the matching row is turned into a ``Record``, and rows after it are
+/// never read, so single-row lookups do not pay for the whole file the way
+/// ``read_csv_records`` does.
+fn find_csv_record(path: &str, key: &str, value: &str) -> Result<Option<Record>, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(BufReader::new(file));
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|h| h.trim_start_matches('\u{feff}').to_string())
+        .collect();
+    let Some(key_idx) = headers.iter().position(|h| h == key) else {
+        return Ok(None);
+    };
+
+    let mut row = csv::StringRecord::new();
+    while reader.read_record(&mut row).map_err(|e| e.to_string())? {
+        if row.get(key_idx) != Some(value) {
+            continue;
+        }
+        let record = headers
+            .iter()
+            .zip(row.iter())
+            .map(|(h, f)| (h.clone(), f.to_string()))
+            .collect();
+        return Ok(Some(record));
+    }
+    Ok(None)
+}
+
+fn count_csv_rows(path: &str) -> Result<usize, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(BufReader::new(file));
+    let mut count = 0;
+    for row in reader.records() {
+        row.map_err(|e| e.to_string())?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// ``write_bom`` prepends a UTF-8 BOM so Excel detects the encoding; tools
+/// such as pandas read it into the first column name unless told
+/// ``utf-8-sig``, so callers may turn it off.
+fn write_csv_records(path: &str, records: &[Record], write_bom: bool) -> Result<(), String> {
     let mut file = fs::File::create(path).map_err(|e| e.to_string())?;
-    file.write_all(bom).map_err(|e| e.to_string())?;
+    if write_bom {
+        file.write_all(b"\xef\xbb\xbf").map_err(|e| e.to_string())?;
+    }
 
     let mut writer = csv::Writer::from_writer(file);
     writer
@@ -83,6 +238,17 @@ fn write_csv_records(path: &str, records: &[Record]) -> Result<(), String> {
     Ok(())
 }
 
+/// Write to a sibling temp file and rename it over ``path``, so readers
+/// never see a half-written history file.
+fn write_csv_records_atomic(path: &str, records: &[Record], write_bom: bool) -> Result<(), String> {
+    let tmp_path = format!("{}.tmp", path);
+    if let Err(e) = write_csv_records(&tmp_path, records, write_bom) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+}
+
 fn get_update_datetime(record: &Record) -> String {
     record
         .get("update_datetime")
@@ -209,14 +375,41 @@ fn build_history_entry(record: &Record) -> Record {
 
 // ── Public functions exposed to Python ───────────────────────────────────
 
+/// Seconds east of UTC for a ``JAVDB_HISTORY_TZ`` value, ``None`` when it is
+/// not ``UTC`` / ``Z`` or a fixed offset.  Lets the Python history writers
+/// share this parser.
+#[pyfunction]
+pub fn parse_history_tz_offset(value: &str) -> Option<i32> {
+    parse_tz_offset(value).map(|tz| tz.local_minus_utc())
+}
+
+/// ``merge_duplicates`` folds the torrent categories of older rows for the
+/// same href into the newest one instead of dropping them.  ``dedup_by_code``
+/// also collapses rows whose hrefs differ but whose ``video_code`` matches,
+/// keeping the most recently updated href.  Merged rows are cleaned with
+/// ``policy`` (default ``PriorityPolicy()``), as on save.
 #[pyfunction]
-#[pyo3(signature = (history_file, phase=None))]
+#[pyo3(signature = (
+    history_file,
+    phase=None,
+    write_bom=true,
+    merge_duplicates=false,
+    dedup_by_code=false,
+    policy=None
+))]
 pub fn load_parsed_movies_history(
     py: Python<'_>,
     history_file: &str,
     phase: Option<i32>,
+    write_bom: bool,
+    merge_duplicates: bool,
+    dedup_by_code: bool,
+    policy: Option<PriorityPolicy>,
 ) -> PyResult<PyObject> {
-    let result = py.allow_threads(|| load_history_impl(history_file, phase));
+    let policy = policy.unwrap_or_default();
+    let result = py.allow_threads(|| {
+        load_history_impl(history_file, phase, write_bom, merge_duplicates, dedup_by_code, &policy)
+    });
 
     match result {
         Ok(history) => {
@@ -246,6 +439,10 @@ pub fn load_parsed_movies_history(
 fn load_history_impl(
     history_file: &str,
     phase: Option<i32>,
+    write_bom: bool,
+    merge_duplicates: bool,
+    dedup_by_code: bool,
+    policy: &PriorityPolicy,
 ) -> Result<HashMap<String, Record>, String> {
     let mut history: HashMap<String, Record> = HashMap::new();
 
@@ -256,23 +453,43 @@ fn load_history_impl(
 
     let (_headers, records) = read_csv_records(history_file)?;
 
-    // Dedup: keep most recent record per href
+    // Dedup: keep most recent record per href, optionally folding in the
+    // categories of the rows it replaces
     let mut href_records: HashMap<String, Record> = HashMap::new();
+    let mut merged_hrefs: HashSet<String> = HashSet::new();
     for row in &records {
         let href = row.get("href").cloned().unwrap_or_default();
         if href.is_empty() {
             continue;
         }
-        if let Some(existing) = href_records.get(&href) {
+        if let Some(existing) = href_records.get_mut(&href) {
             let existing_date = get_update_datetime(existing);
             let current_date = get_update_datetime(row);
             if current_date > existing_date {
-                href_records.insert(href, row.clone());
+                let older = std::mem::replace(existing, row.clone());
+                if merge_duplicates {
+                    merge_category_columns(existing, &older);
+                }
+            } else if merge_duplicates {
+                merge_category_columns(existing, row);
+            }
+            if merge_duplicates {
+                merged_hrefs.insert(href);
             }
         } else {
             href_records.insert(href, row.clone());
         }
     }
+    if dedup_by_code {
+        merged_hrefs.extend(collapse_by_video_code(&mut href_records));
+    }
+
+    // A recovered category may be one the newer row had superseded.
+    for href in &merged_hrefs {
+        if let Some(rec) = href_records.get_mut(href) {
+            apply_priority_cleanup(rec, policy);
+        }
+    }
 
     // Process deduplicated records
     for (href, row) in &href_records {
@@ -296,7 +513,7 @@ fn load_history_impl(
             "Found {} duplicate records, cleaning up history file",
             records.len() - href_records.len()
         );
-        let _ = cleanup_history_impl(history_file, &href_records);
+        let _ = cleanup_history_impl(history_file, &href_records, write_bom);
     }
 
     // Log phase counts
@@ -321,13 +538,15 @@ fn load_history_impl(
 }
 
 #[pyfunction]
+#[pyo3(signature = (history_file, href_records, write_bom=true))]
 pub fn cleanup_history_file(
     py: Python<'_>,
     history_file: &str,
     href_records: HashMap<String, HashMap<String, String>>,
+    write_bom: bool,
 ) -> PyResult<()> {
     py.allow_threads(|| {
-        let _ = cleanup_history_impl(history_file, &href_records);
+        let _ = cleanup_history_impl(history_file, &href_records, write_bom);
     });
     Ok(())
 }
@@ -335,6 +554,7 @@ pub fn cleanup_history_file(
 fn cleanup_history_impl(
     history_file: &str,
     href_records: &HashMap<String, Record>,
+    write_bom: bool,
 ) -> Result<(), String> {
     let mut sorted_records: Vec<Record> = href_records.values().cloned().collect();
     sorted_records.sort_by(|a, b| get_update_datetime(b).cmp(&get_update_datetime(a)));
@@ -343,7 +563,7 @@ fn cleanup_history_impl(
         normalize_record(rec);
     }
 
-    write_csv_records(history_file, &sorted_records)?;
+    write_csv_records(history_file, &sorted_records, write_bom)?;
     info!(
         "Cleaned up history file: removed duplicates, kept {} unique records",
         sorted_records.len()
@@ -352,30 +572,41 @@ fn cleanup_history_impl(
 }
 
 #[pyfunction]
-#[pyo3(signature = (history_file, max_records=1000))]
+#[pyo3(signature = (history_file, max_records=1000, slack=0.0, write_bom=true))]
 pub fn maintain_history_limit(
     py: Python<'_>,
     history_file: &str,
     max_records: usize,
+    slack: f64,
+    write_bom: bool,
 ) -> PyResult<()> {
     py.allow_threads(|| {
-        if let Err(e) = maintain_history_limit_impl(history_file, max_records) {
+        if let Err(e) = maintain_history_limit_impl(history_file, max_records, slack, write_bom) {
             error!("Error maintaining history limit: {}", e);
         }
     });
     Ok(())
 }
 
-fn maintain_history_limit_impl(history_file: &str, max_records: usize) -> Result<(), String> {
+/// Trim to the newest ``max_records`` once the file grows past
+/// ``max_records * (1 + slack)``.  A non-zero ``slack`` gives a hysteresis
+/// band so runs hovering near the limit do not rewrite the file every time.
+fn maintain_history_limit_impl(
+    history_file: &str,
+    max_records: usize,
+    slack: f64,
+    write_bom: bool,
+) -> Result<(), String> {
     if !Path::new(history_file).exists() {
         return Ok(());
     }
 
-    let (_headers, records) = read_csv_records(history_file)?;
-    if records.len() <= max_records {
+    let threshold = (max_records as f64 * (1.0 + slack.max(0.0))).floor() as usize;
+    if count_csv_rows(history_file)? <= threshold {
         return Ok(());
     }
 
+    let (_headers, records) = read_csv_records(history_file)?;
     let mut sorted = records;
     sorted.sort_by(|a, b| get_update_datetime(a).cmp(&get_update_datetime(b)));
     let skip_count = sorted.len().saturating_sub(max_records);
@@ -386,7 +617,7 @@ fn maintain_history_limit_impl(history_file: &str, max_records: usize) -> Result
         normalize_record(rec);
     }
 
-    write_csv_records(history_file, &normalised)?;
+    write_csv_records(history_file, &normalised, write_bom)?;
     info!(
         "Maintained history limit: kept {} newest records, removed oldest entries",
         normalised.len()
@@ -395,7 +626,54 @@ fn maintain_history_limit_impl(history_file: &str, max_records: usize) -> Result
 }
 
 #[pyfunction]
-#[pyo3(signature = (history_file, href, phase, video_code, magnet_links=None, size_links=None, file_count_links=None, resolution_links=None))]
+#[pyo3(signature = (history_file, hrefs, write_bom=true))]
+pub fn remove_from_history(
+    py: Python<'_>,
+    history_file: &str,
+    hrefs: Vec<String>,
+    write_bom: bool,
+) -> PyResult<usize> {
+    Ok(py.allow_threads(|| match remove_from_history_impl(history_file, &hrefs, write_bom) {
+        Ok(removed) => removed,
+        Err(e) => {
+            error!("Error removing entries from history: {}", e);
+            0
+        }
+    }))
+}
+
+fn remove_from_history_impl(
+    history_file: &str,
+    hrefs: &[String],
+    write_bom: bool,
+) -> Result<usize, String> {
+    if hrefs.is_empty() || !Path::new(history_file).exists() {
+        return Ok(0);
+    }
+
+    let targets: HashSet<&str> = hrefs.iter().map(|h| h.as_str()).collect();
+    let (_headers, records) = read_csv_records(history_file)?;
+    let total = records.len();
+
+    let mut kept: Vec<Record> = records
+        .into_iter()
+        .filter(|r| !targets.contains(r.get("href").map_or("", |h| h.as_str())))
+        .collect();
+    let removed = total - kept.len();
+    if removed == 0 {
+        return Ok(0);
+    }
+
+    for rec in &mut kept {
+        normalize_record(rec);
+    }
+    write_csv_records_atomic(history_file, &kept, write_bom)?;
+    info!("Removed {} records from history", removed);
+    Ok(removed)
+}
+
+#[pyfunction]
+#[pyo3(signature = (history_file, href, phase, video_code, magnet_links=None, size_links=None, file_count_links=None, resolution_links=None, policy=None, write_bom=true))]
 pub fn save_parsed_movie_to_history(
     py: Python<'_>,
     history_file: &str,
@@ -406,7 +684,10 @@ pub fn save_parsed_movie_to_history(
     size_links: Option<HashMap<String, String>>,
     #[allow(unused)] file_count_links: Option<HashMap<String, i64>>,
     #[allow(unused)] resolution_links: Option<HashMap<String, String>>,
-) -> PyResult<()> {
+    policy: Option<PriorityPolicy>,
+    write_bom: bool,
+) -> PyResult<Option<String>> {
+    let policy = policy.unwrap_or_default();
     let phase_str = phase.str()?.to_string();
     let links = magnet_links.unwrap_or_else(|| {
         let mut m = HashMap::new();
@@ -415,14 +696,31 @@ pub fn save_parsed_movie_to_history(
     });
     let sizes = size_links.unwrap_or_default();
 
-    py.allow_threads(|| {
-        if let Err(e) = save_history_impl(history_file, href, &phase_str, video_code, &links, &sizes) {
-            error!("Error writing to history file: {}", e);
+    Ok(py.allow_threads(|| {
+        match save_history_impl(
+            history_file, href, &phase_str, video_code, &links, &sizes, &policy, write_bom,
+        ) {
+            Ok(action) => Some(action.to_string()),
+            Err(e) => {
+                error!("Error writing to history file: {}", e);
+                None
+            }
         }
-    });
-    Ok(())
+    }))
+}
+
+/// Columns whose change makes a save count as ``"updated"``; visit
+/// timestamps alone leave it ``"unchanged"``.
+fn record_content(record: &Record) -> Vec<Option<String>> {
+    let mut keys = vec!["phase".to_string(), "torrent_type".to_string()];
+    for cat in TORRENT_CATEGORIES {
+        keys.push(cat.to_string());
+        keys.push(format!("size_{}", cat));
+    }
+    keys.iter().map(|k| record.get(k).cloned()).collect()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn save_history_impl(
     history_file: &str,
     href: &str,
@@ -430,30 +728,58 @@ fn save_history_impl(
     video_code: &str,
     magnet_links: &HashMap<String, String>,
     size_links: &HashMap<String, String>,
-) -> Result<(), String> {
-    let current_time = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    let current_date = Local::now().format("%Y-%m-%d").to_string();
+    policy: &PriorityPolicy,
+    write_bom: bool,
+) -> Result<&'static str, String> {
+    let (current_time, current_date) = format_history_time(history_now());
 
     let mut records: Vec<Record> = Vec::new();
-    let mut existing_count = 0u32;
-    let mut updated_record: Option<Record> = None;
+    let mut matches: Vec<Record> = Vec::new();
 
     if Path::new(history_file).exists() {
         let (_headers, existing) = read_csv_records(history_file)?;
-        for mut row in existing {
-            if row.get("href").map(|s| s.as_str()) == Some(href) {
-                existing_count += 1;
-                update_existing_record(&mut row, phase, magnet_links, size_links, &current_time, &current_date);
-                apply_priority_cleanup(&mut row);
-                updated_record = Some(row);
+        // Rows under the same title with another href only stand in when
+        // the href itself has no row; otherwise they are left untouched.
+        let same_href = |row: &Record| row.get("href").map(|s| s.as_str()) == Some(href);
+        let by_href = existing.iter().any(same_href);
+        for row in existing {
+            let matched = if by_href {
+                same_href(&row)
+            } else {
+                row.get("video_code")
+                    .is_some_and(|code| video_codes_equal(code, video_code))
+            };
+            if matched {
+                matches.push(row);
             } else {
                 records.push(row);
             }
         }
     }
 
+    let existing_count = matches.len();
+    let mut content_changed = false;
+    let mut updated_record: Option<Record> = None;
+    if !matches.is_empty() {
+        // Fold every matching row into one so no category is lost, under
+        // the href being saved.
+        let mut row = matches.pop().unwrap();
+        let before = record_content(&row);
+        for other in &matches {
+            merge_category_columns(&mut row, other);
+        }
+        content_changed |= row.get("href").map(|s| s.as_str()) != Some(href);
+        row.insert("href".into(), href.into());
+        update_existing_record(&mut row, phase, magnet_links, size_links, &current_time, &current_date);
+        apply_priority_cleanup(&mut row, policy);
+        content_changed |= record_content(&row) != before;
+        updated_record = Some(row);
+    }
+
     if existing_count == 0 {
-        let new_rec = create_new_record(href, phase, video_code, magnet_links, size_links, &current_time, &current_date);
+        let new_rec = create_new_record(
+            href, phase, video_code, magnet_links, size_links, &current_time, &current_date, policy,
+        );
         records.insert(0, new_rec);
         debug!("Added new record for {} with magnet links", href);
     } else {
@@ -462,7 +788,7 @@ fn save_history_impl(
         }
         if existing_count > 1 {
             warn!(
-                "Found {} existing records for {}, keeping the updated one",
+                "Found {} existing records for {}, merged into the updated one",
                 existing_count, href
             );
         }
@@ -473,13 +799,19 @@ fn save_history_impl(
         normalize_record(rec);
     }
 
-    write_csv_records(history_file, &records)?;
+    write_csv_records(history_file, &records, write_bom)?;
     debug!(
         "Updated history for {} (total records: {})",
         href,
         records.len()
     );
-    Ok(())
+    Ok(if existing_count == 0 {
+        "created"
+    } else if content_changed {
+        "updated"
+    } else {
+        "unchanged"
+    })
 }
 
 fn update_existing_record(
@@ -559,6 +891,7 @@ fn update_existing_record(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_new_record(
     href: &str,
     phase: &str,
@@ -567,6 +900,7 @@ fn create_new_record(
     size_links: &HashMap<String, String>,
     current_time: &str,
     current_date: &str,
+    policy: &PriorityPolicy,
 ) -> Record {
     let mut rec = HashMap::new();
     rec.insert("href".into(), href.into());
@@ -587,26 +921,79 @@ fn create_new_record(
         }
     }
 
-    apply_priority_cleanup(&mut rec);
+    apply_priority_cleanup(&mut rec, policy);
     rec
 }
 
-fn apply_priority_cleanup(record: &mut Record) {
-    if record
-        .get("hacked_subtitle")
-        .map(|s| !s.trim().is_empty())
-        .unwrap_or(false)
-    {
-        record.insert("hacked_no_subtitle".into(), String::new());
-        record.insert("size_hacked_no_subtitle".into(), String::new());
+fn apply_priority_cleanup(record: &mut Record, policy: &PriorityPolicy) {
+    if !policy.enabled {
+        return;
     }
-    if record
-        .get("subtitle")
-        .map(|s| !s.trim().is_empty())
-        .unwrap_or(false)
-    {
-        record.insert("no_subtitle".into(), String::new());
-        record.insert("size_no_subtitle".into(), String::new());
+    for (winner, loser) in &policy.pairs {
+        if record
+            .get(winner)
+            .map(|s| !s.trim().is_empty())
+            .unwrap_or(false)
+        {
+            record.insert(loser.clone(), String::new());
+            record.insert(format!("size_{}", loser), String::new());
+        }
+    }
+}
+
+/// Collapse records whose (normalized) ``video_code`` matches into the one
+/// updated most recently, merging the others' torrent categories into it.
+/// Records without a code are left alone.  Returns the surviving hrefs.
+fn collapse_by_video_code(href_records: &mut HashMap<String, Record>) -> Vec<String> {
+    let mut by_code: HashMap<String, Vec<String>> = HashMap::new();
+    for (href, rec) in href_records.iter() {
+        let code = normalize_video_code(rec.get("video_code").map_or("", |c| c.as_str()));
+        if !code.is_empty() {
+            by_code.entry(code).or_default().push(href.clone());
+        }
+    }
+
+    let mut survivors = Vec::new();
+    for (code, mut hrefs) in by_code {
+        if hrefs.len() < 2 {
+            continue;
+        }
+        // Newest first; href breaks ties so the result does not depend on
+        // hash order.
+        hrefs.sort_by(|a, b| {
+            get_update_datetime(&href_records[b])
+                .cmp(&get_update_datetime(&href_records[a]))
+                .then_with(|| a.cmp(b))
+        });
+        let keep = hrefs.remove(0);
+        for href in &hrefs {
+            let other = href_records.remove(href).unwrap();
+            merge_category_columns(href_records.get_mut(&keep).unwrap(), &other);
+        }
+        debug!("Collapsed {} records for {} into {}", hrefs.len() + 1, code, keep);
+        survivors.push(keep);
+    }
+    survivors
+}
+
+/// Copy each torrent category (and its size) from ``other`` into ``record``
+/// when ``record`` has none or ``other``'s is newer.  A cell's date is its
+/// ``[date]`` prefix, else the row's update time.
+fn merge_category_columns(record: &mut Record, other: &Record) {
+    let cell_date = |rec: &Record, content: &str| {
+        extract_date_from_content(content).unwrap_or_else(|| get_update_datetime(rec))
+    };
+    for cat in TORRENT_CATEGORIES {
+        let theirs = other.get(*cat).map(|s| s.trim()).unwrap_or("");
+        if theirs.is_empty() {
+            continue;
+        }
+        let ours = record.get(*cat).map(|s| s.trim().to_string()).unwrap_or_default();
+        if ours.is_empty() || cell_date(other, theirs) > cell_date(record, &ours) {
+            let size_key = format!("size_{}", cat);
+            record.insert(cat.to_string(), theirs.to_string());
+            record.insert(size_key.clone(), other.get(&size_key).cloned().unwrap_or_default());
+        }
     }
 }
 
@@ -620,11 +1007,12 @@ fn extract_date_from_content(content: &str) -> Option<String> {
 }
 
 #[pyfunction]
-pub fn validate_history_file(py: Python<'_>, history_file: &str) -> PyResult<bool> {
-    Ok(py.allow_threads(|| validate_history_impl(history_file)))
+#[pyo3(signature = (history_file, write_bom=true))]
+pub fn validate_history_file(py: Python<'_>, history_file: &str, write_bom: bool) -> PyResult<bool> {
+    Ok(py.allow_threads(|| validate_history_impl(history_file, write_bom)))
 }
 
-fn validate_history_impl(history_file: &str) -> bool {
+fn validate_history_impl(history_file: &str, write_bom: bool) -> bool {
     if !Path::new(history_file).exists() {
         return true;
     }
@@ -651,7 +1039,7 @@ fn validate_history_impl(history_file: &str) -> bool {
         normalize_record(rec);
     }
 
-    match write_csv_records(history_file, &converted) {
+    match write_csv_records(history_file, &converted, write_bom) {
         Ok(()) => {
             info!("Successfully converted history file to new format");
             true
@@ -663,6 +1051,155 @@ fn validate_history_impl(history_file: &str) -> bool {
     }
 }
 
+/// Read-only schema check of the history header against ``CSV_HEADER``.
+///
+/// Returns one line per problem (``missing: X``, ``extra: X``,
+/// ``misordered: X ...``); empty when the file matches or does not exist.
+#[pyfunction]
+pub fn check_history_schema(py: Python<'_>, history_file: &str) -> PyResult<Vec<String>> {
+    Ok(py.allow_threads(|| {
+        if !Path::new(history_file).exists() {
+            return Vec::new();
+        }
+        match read_csv_headers(history_file) {
+            Ok(headers) => schema_problems(&headers),
+            Err(e) => {
+                error!("Error checking history schema: {}", e);
+                vec![format!("unreadable: {}", e)]
+            }
+        }
+    }))
+}
+
+fn read_csv_headers(path: &str) -> Result<Vec<String>, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(BufReader::new(file));
+    Ok(reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|h| h.trim_start_matches('\u{feff}').to_string())
+        .collect())
+}
+
+fn schema_problems(headers: &[String]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for col in CSV_HEADER {
+        if !headers.iter().any(|h| h == col) {
+            problems.push(format!("missing: {}", col));
+        }
+    }
+    for h in headers {
+        if !CSV_HEADER.contains(&h.as_str()) {
+            problems.push(format!("extra: {}", h));
+        }
+    }
+
+    // Compare relative order of the columns both sides share.
+    let expected: Vec<&str> = CSV_HEADER
+        .iter()
+        .copied()
+        .filter(|c| headers.iter().any(|h| h == c))
+        .collect();
+    let found: Vec<&str> = headers
+        .iter()
+        .map(|h| h.as_str())
+        .filter(|h| CSV_HEADER.contains(h))
+        .collect();
+    for (i, (want, got)) in expected.iter().zip(found.iter()).enumerate() {
+        if want != got {
+            problems.push(format!(
+                "misordered: {} at position {}, expected {}",
+                got, i, want
+            ));
+        }
+    }
+    problems
+}
+
+/// Counts summed by ``aggregate_history_statistics``.
+#[derive(Debug, Default)]
+struct HistoryStats {
+    total_records: usize,
+    hrefs: HashSet<String>,
+    by_category: HashMap<String, usize>,
+    by_phase: HashMap<String, usize>,
+    files_read: Vec<String>,
+    missing_files: Vec<String>,
+    unreadable_files: Vec<String>,
+}
+
+impl HistoryStats {
+    fn add_file(&mut self, history_file: &str) {
+        if !Path::new(history_file).exists() {
+            warn!("History file {} not found, skipping in statistics", history_file);
+            self.missing_files.push(history_file.to_string());
+            return;
+        }
+        let records = match read_csv_records(history_file) {
+            Ok((_, r)) => r,
+            Err(e) => {
+                error!("Error reading {} for statistics: {}", history_file, e);
+                self.unreadable_files.push(history_file.to_string());
+                return;
+            }
+        };
+
+        for mut row in records {
+            normalize_record(&mut row);
+            self.total_records += 1;
+            if let Some(href) = row.get("href").filter(|h| !h.is_empty()) {
+                self.hrefs.insert(href.clone());
+            }
+            let phase = row.get("phase").cloned().unwrap_or_default();
+            *self.by_phase.entry(phase).or_insert(0) += 1;
+            for cat in TORRENT_CATEGORIES {
+                if row.get(*cat).is_some_and(|v| !v.trim().is_empty()) {
+                    *self.by_category.entry(cat.to_string()).or_insert(0) += 1;
+                }
+            }
+        }
+        self.files_read.push(history_file.to_string());
+    }
+}
+
+fn aggregate_history_stats_impl(files: &[String]) -> HistoryStats {
+    let mut stats = HistoryStats::default();
+    for cat in TORRENT_CATEGORIES {
+        stats.by_category.insert(cat.to_string(), 0);
+    }
+    for file in files {
+        stats.add_file(file);
+    }
+    stats
+}
+
+/// Combined statistics over several history files (e.g. one per year).
+///
+/// ``unique_movies`` counts distinct hrefs across all files, so a movie
+/// present in two shards is counted once there but twice in
+/// ``total_records``.  Missing or unreadable files are listed rather than
+/// failing the whole report.
+#[pyfunction]
+pub fn aggregate_history_statistics(
+    py: Python<'_>,
+    files: Vec<String>,
+) -> HashMap<String, PyObject> {
+    let stats = py.allow_threads(|| aggregate_history_stats_impl(&files));
+
+    let mut report = HashMap::new();
+    report.insert("total_records".to_string(), stats.total_records.to_object(py));
+    report.insert("unique_movies".to_string(), stats.hrefs.len().to_object(py));
+    report.insert("by_category".to_string(), stats.by_category.to_object(py));
+    report.insert("by_phase".to_string(), stats.by_phase.to_object(py));
+    report.insert("files_read".to_string(), stats.files_read.to_object(py));
+    report.insert("missing_files".to_string(), stats.missing_files.to_object(py));
+    report.insert("unreadable_files".to_string(), stats.unreadable_files.to_object(py));
+    report
+}
+
 // ── Pure logic functions ─────────────────────────────────────────────────
 
 #[pyfunction]
@@ -782,7 +1319,7 @@ pub fn should_skip_recent_yesterday_release(
         return Ok(false);
     }
 
-    let cutoff = (Local::now() - chrono::Duration::days(1))
+    let cutoff = (history_now() - chrono::Duration::days(1))
         .format("%Y-%m-%d")
         .to_string();
     let prefix = visited_str.get(..10).unwrap_or(&visited_str);
@@ -831,20 +1368,22 @@ pub fn should_skip_recent_today_release(
         return Ok(false);
     }
 
-    let cutoff = Local::now().format("%Y-%m-%d").to_string();
+    let cutoff = format_history_time(history_now()).1;
     let prefix = visited_str.get(..10).unwrap_or(&visited_str);
 
     Ok(prefix >= cutoff.as_str())
 }
 
 #[pyfunction]
+#[pyo3(signature = (history_file, visited_hrefs, write_bom=true))]
 pub fn batch_update_last_visited(
     py: Python<'_>,
     history_file: &str,
     visited_hrefs: HashSet<String>,
+    write_bom: bool,
 ) -> PyResult<()> {
     py.allow_threads(|| {
-        if let Err(e) = batch_update_last_visited_impl(history_file, &visited_hrefs) {
+        if let Err(e) = batch_update_last_visited_impl(history_file, &visited_hrefs, write_bom) {
             error!("Error batch-updating last_visited_datetime: {}", e);
         }
     });
@@ -854,12 +1393,13 @@ pub fn batch_update_last_visited(
 fn batch_update_last_visited_impl(
     history_file: &str,
     visited_hrefs: &HashSet<String>,
+    write_bom: bool,
 ) -> Result<(), String> {
     if visited_hrefs.is_empty() || !Path::new(history_file).exists() {
         return Ok(());
     }
 
-    let current_time = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let current_time = format_history_time(history_now()).0;
     let (_headers, mut records) = read_csv_records(history_file)?;
 
     let mut updated = 0usize;
@@ -876,7 +1416,7 @@ fn batch_update_last_visited_impl(
         normalize_record(rec);
     }
 
-    write_csv_records(history_file, &records)?;
+    write_csv_records(history_file, &records, write_bom)?;
 
     if updated > 0 {
         debug!("Updated last_visited_datetime for {} movies", updated);
@@ -940,6 +1480,50 @@ pub fn should_process_movie(
     Ok((false, hist_obj))
 }
 
+/// Missing torrent types for every scraped movie already in history.
+///
+/// Batch form of ``get_missing_torrent_types``: only hrefs with at least one
+/// upgrade are returned.  Movies not yet in history are left out.
+#[pyfunction]
+pub fn compute_upgrades(
+    history_data: &Bound<'_, PyDict>,
+    scraped: Vec<(String, HashMap<String, String>)>,
+) -> PyResult<HashMap<String, Vec<String>>> {
+    let mut history_types: HashMap<String, Vec<String>> = HashMap::new();
+    for (href, _) in &scraped {
+        let entry = match history_data.get_item(href)? {
+            Some(v) => v,
+            None => continue,
+        };
+        let entry_dict: &Bound<'_, PyDict> = entry.downcast()?;
+        let types = match entry_dict.get_item("torrent_types")? {
+            Some(v) => v.extract::<Vec<String>>()?,
+            None => vec!["no_subtitle".to_string()],
+        };
+        history_types.insert(href.clone(), types);
+    }
+    Ok(compute_upgrades_impl(&history_types, scraped))
+}
+
+fn compute_upgrades_impl(
+    history_types: &HashMap<String, Vec<String>>,
+    scraped: Vec<(String, HashMap<String, String>)>,
+) -> HashMap<String, Vec<String>> {
+    let mut upgrades = HashMap::new();
+    for (href, magnet_links) in scraped {
+        let hist = match history_types.get(&href) {
+            Some(t) => t.clone(),
+            None => continue,
+        };
+        let missing = get_missing_torrent_types(hist, determine_torrent_types(magnet_links));
+        if !missing.is_empty() {
+            upgrades.insert(href, missing);
+        }
+    }
+    debug!("Computed torrent upgrades for {} movies", upgrades.len());
+    upgrades
+}
+
 #[pyfunction]
 pub fn check_torrent_in_history(
     py: Python<'_>,
@@ -955,38 +1539,31 @@ fn check_torrent_impl(history_file: &str, href: &str, torrent_type: &str) -> boo
         return false;
     }
 
-    let records = match read_csv_records(history_file) {
-        Ok((_, r)) => r,
+    let row = match find_csv_record(history_file, "href", href) {
+        Ok(Some(r)) => r,
+        Ok(None) => return false,
         Err(e) => {
             error!("Error checking torrent in history: {}", e);
             return false;
         }
     };
 
-    for row in &records {
-        if row.get("href").map(|s| s.as_str()) != Some(href) {
-            continue;
-        }
-
-        // Old format
-        if let Some(tt) = row.get("torrent_type") {
-            let types: Vec<&str> = tt.split(',').map(|s| s.trim()).collect();
-            return types.contains(&torrent_type);
-        }
-
-        // New format
-        let content = row.get(torrent_type).map(|s| s.trim()).unwrap_or("");
-        if content.is_empty() {
-            return false;
-        }
-        if content.starts_with('[') && content.contains(']') {
-            let after = content.splitn(2, ']').nth(1).unwrap_or("");
-            return after.starts_with("magnet:");
-        }
-        return content.starts_with("magnet:");
+    // Old format
+    if let Some(tt) = row.get("torrent_type") {
+        let types: Vec<&str> = tt.split(',').map(|s| s.trim()).collect();
+        return types.contains(&torrent_type);
     }
 
-    false
+    // New format
+    let content = row.get(torrent_type).map(|s| s.trim()).unwrap_or("");
+    if content.is_empty() {
+        return false;
+    }
+    if content.starts_with('[') && content.contains(']') {
+        let after = content.splitn(2, ']').nth(1).unwrap_or("");
+        return after.starts_with("magnet:");
+    }
+    content.starts_with("magnet:")
 }
 
 #[pyfunction]
@@ -1123,13 +1700,17 @@ pub fn is_downloaded_torrent(torrent_content: &str) -> bool {
 }
 
 #[pyfunction]
+#[pyo3(signature = (history_file, href, video_code, torrent_type, policy=None, write_bom=true))]
 pub fn mark_torrent_as_downloaded(
     py: Python<'_>,
     history_file: &str,
     href: &str,
     video_code: &str,
     torrent_type: &str,
+    policy: Option<PriorityPolicy>,
+    write_bom: bool,
 ) -> PyResult<bool> {
+    let policy = policy.unwrap_or_default();
     let mut links = HashMap::new();
     links.insert(
         torrent_type.to_string(),
@@ -1137,10 +1718,14 @@ pub fn mark_torrent_as_downloaded(
     );
 
     let empty_sizes = HashMap::new();
-    let result = py.allow_threads(|| save_history_impl(history_file, href, "2", video_code, &links, &empty_sizes));
+    let result = py.allow_threads(|| {
+        save_history_impl(
+            history_file, href, "2", video_code, &links, &empty_sizes, &policy, write_bom,
+        )
+    });
 
     match result {
-        Ok(()) => {
+        Ok(_) => {
             debug!(
                 "Marked {} as downloaded for {} ({})",
                 torrent_type, video_code, href
@@ -1158,6 +1743,25 @@ pub fn mark_torrent_as_downloaded(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_history_time_fixed_offset() {
+        let utc = DateTime::parse_from_rfc3339("2024-03-31T20:30:00Z").unwrap();
+        let tz = parse_tz_offset("+08:00").unwrap();
+        assert_eq!(
+            format_history_time(utc.with_timezone(&tz)),
+            ("2024-04-01 04:30:00".to_string(), "2024-04-01".to_string())
+        );
+        assert_eq!(
+            format_history_time(utc.with_timezone(&parse_tz_offset("UTC").unwrap())).1,
+            "2024-03-31"
+        );
+        assert_eq!(parse_tz_offset("-0530"), FixedOffset::west_opt(5 * 3600 + 30 * 60));
+        assert_eq!(parse_tz_offset("+9"), None);
+        assert_eq!(parse_tz_offset("Asia/Tokyo"), None);
+        assert_eq!(parse_history_tz_offset("+08:00"), Some(8 * 3600));
+        assert_eq!(parse_history_tz_offset("z"), Some(0));
+    }
+
     #[test]
     fn test_determine_torrent_types() {
         let mut links = HashMap::new();
@@ -1175,6 +1779,263 @@ mod tests {
         assert_eq!(missing, vec!["subtitle"]);
     }
 
+    #[test]
+    fn test_compute_upgrades() {
+        let mut history = HashMap::new();
+        history.insert("/v/aaa".to_string(), vec!["no_subtitle".to_string()]);
+        history.insert("/v/bbb".to_string(), vec!["subtitle".to_string()]);
+
+        let links = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let scraped = vec![
+            (
+                "/v/aaa".to_string(),
+                links(&[("subtitle", "magnet:a"), ("hacked_subtitle", "magnet:b")]),
+            ),
+            ("/v/bbb".to_string(), links(&[("subtitle", "magnet:c")])),
+            ("/v/new".to_string(), links(&[("subtitle", "magnet:d")])),
+        ];
+
+        let upgrades = compute_upgrades_impl(&history, scraped);
+        assert_eq!(upgrades.len(), 1);
+        assert_eq!(upgrades["/v/aaa"], vec!["hacked_subtitle", "subtitle"]);
+    }
+
+    #[test]
+    fn test_remove_from_history() {
+        let path =
+            std::env::temp_dir().join(format!("history_remove_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let records: Vec<Record> = ["/v/a", "/v/b", "/v/c"]
+            .iter()
+            .map(|href| {
+                let mut rec = Record::new();
+                rec.insert("href".into(), href.to_string());
+                rec.insert("phase".into(), "1".into());
+                rec
+            })
+            .collect();
+        write_csv_records(path, &records, true).unwrap();
+
+        let removed =
+            remove_from_history_impl(path, &["/v/b".to_string(), "/v/zzz".to_string()], true).unwrap();
+        assert_eq!(removed, 1);
+
+        let (_headers, left) = read_csv_records(path).unwrap();
+        let hrefs: Vec<&str> = left.iter().map(|r| r["href"].as_str()).collect();
+        assert_eq!(hrefs, vec!["/v/a", "/v/c"]);
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_schema_problems() {
+        let good: Vec<String> = CSV_HEADER.iter().map(|h| h.to_string()).collect();
+        assert!(schema_problems(&good).is_empty());
+
+        let path =
+            std::env::temp_dir().join(format!("history_schema_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let header: Vec<&str> = CSV_HEADER.iter().copied().filter(|h| *h != "phase").collect();
+        fs::write(path, format!("\u{feff}{}\n/v/a\n", header.join(","))).unwrap();
+        let headers = read_csv_headers(path).unwrap();
+        assert_eq!(schema_problems(&headers), vec!["missing: phase"]);
+        fs::remove_file(path).unwrap();
+
+        let mut swapped = good.clone();
+        swapped.swap(0, 1);
+        swapped.push("notes".to_string());
+        let problems = schema_problems(&swapped);
+        assert_eq!(problems[0], "extra: notes");
+        assert_eq!(problems.len(), 3);
+    }
+
+    #[test]
+    fn test_maintain_history_limit_slack() {
+        let path =
+            std::env::temp_dir().join(format!("history_limit_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let records: Vec<Record> = (0..11)
+            .map(|i| {
+                let mut rec = Record::new();
+                rec.insert("href".into(), format!("/v/{i:02}"));
+                rec.insert("update_datetime".into(), format!("2025-01-{:02} 00:00:00", i + 1));
+                rec
+            })
+            .collect();
+        write_csv_records(path, &records, true).unwrap();
+        let before = fs::metadata(path).unwrap().modified().unwrap();
+
+        // 11 rows, limit 10, slack 20% -> threshold 12: left untouched.
+        maintain_history_limit_impl(path, 10, 0.2, true).unwrap();
+        assert_eq!(fs::metadata(path).unwrap().modified().unwrap(), before);
+        assert_eq!(count_csv_rows(path).unwrap(), 11);
+
+        // No slack: trimmed to the newest 10.
+        maintain_history_limit_impl(path, 10, 0.0, true).unwrap();
+        let (_headers, left) = read_csv_records(path).unwrap();
+        assert_eq!(left.len(), 10);
+        assert!(left.iter().all(|r| r["href"] != "/v/00"));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_save_history_reports_action() {
+        let path =
+            std::env::temp_dir().join(format!("history_save_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = fs::remove_file(path);
+
+        let mut links = HashMap::new();
+        links.insert("no_subtitle".to_string(), "magnet:?xt=urn:btih:aaa".to_string());
+        let sizes = HashMap::new();
+        let policy = PriorityPolicy::default();
+
+        let action = save_history_impl(path, "/v/a", "1", "ABC-123", &links, &sizes, &policy, true).unwrap();
+        assert_eq!(action, "created");
+
+        let action = save_history_impl(path, "/v/a", "1", "ABC-123", &links, &sizes, &policy, true).unwrap();
+        assert_eq!(action, "unchanged");
+
+        links.insert("subtitle".to_string(), "magnet:?xt=urn:btih:bbb".to_string());
+        let action = save_history_impl(path, "/v/a", "1", "ABC-123", &links, &sizes, &policy, true).unwrap();
+        assert_eq!(action, "updated");
+
+        // Same title under a differently formatted code updates, not duplicates,
+        // and moves the row to the new href.
+        let action = save_history_impl(path, "/v/a2", "1", "abc-00123", &links, &sizes, &policy, true).unwrap();
+        assert_eq!(action, "updated");
+        assert_eq!(count_csv_rows(path).unwrap(), 1);
+        assert!(fs::read(path).unwrap().starts_with(b"\xef\xbb\xbf"));
+
+        let action = save_history_impl(path, "/v/a2", "1", "ABC-123", &links, &sizes, &policy, false).unwrap();
+        assert_eq!(action, "unchanged");
+        let bytes = fs::read(path).unwrap();
+        assert!(bytes.starts_with(b"href,"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_save_history_merges_rows_sharing_a_code() {
+        let path = std::env::temp_dir().join(format!("history_code_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let row = |href: &str, code: &str, cat: &str| {
+            let mut rec = Record::new();
+            rec.insert("href".into(), href.into());
+            rec.insert("phase".into(), "1".into());
+            rec.insert("video_code".into(), code.into());
+            rec.insert("update_datetime".into(), "2024-01-01 10:00:00".into());
+            rec.insert(cat.into(), format!("[2024-01-01]magnet:?xt=urn:btih:{}", cat));
+            rec
+        };
+        let records = vec![
+            row("/v/a", "ABC-123", "hacked_subtitle"),
+            row("/v/b", "abc-00123", "subtitle"),
+            row("/v/x", "XYZ-001", "no_subtitle"),
+        ];
+        write_csv_records(path, &records, true).unwrap();
+
+        let links = HashMap::new();
+        let sizes = HashMap::new();
+        let policy = PriorityPolicy::default();
+        let action =
+            save_history_impl(path, "/v/c", "1", "ABC-123", &links, &sizes, &policy, true).unwrap();
+        assert_eq!(action, "updated");
+
+        let (_headers, rows) = read_csv_records(path).unwrap();
+        let hrefs: Vec<&str> = rows.iter().map(|r| r["href"].as_str()).collect();
+        assert_eq!(hrefs, ["/v/c", "/v/x"]);
+        assert!(rows[0]["hacked_subtitle"].ends_with("btih:hacked_subtitle"));
+        assert!(rows[0]["subtitle"].ends_with("btih:subtitle"));
+
+        // An exact href match wins; the same-code row is kept as is.
+        write_csv_records(path, &records, true).unwrap();
+        save_history_impl(path, "/v/b", "1", "ABC-123", &links, &sizes, &policy, true).unwrap();
+        let (_headers, rows) = read_csv_records(path).unwrap();
+        let hrefs: Vec<&str> = rows.iter().map(|r| r["href"].as_str()).collect();
+        assert_eq!(hrefs, ["/v/b", "/v/a", "/v/x"]);
+        assert_eq!(rows[0]["hacked_subtitle"], "");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_check_torrent_streams_until_match() {
+        let path =
+            std::env::temp_dir().join(format!("history_stream_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(
+            path,
+            "href,video_code,subtitle,no_subtitle\n\
+             /v/aaa,AAA-001,[2025-01-01]magnet:a,\n\
+             /v/bbb,BBB-002,,magnet:b\n",
+        )
+        .unwrap();
+
+        let (_headers, records) = read_csv_records(path).unwrap();
+        for (href, tt, expected) in [
+            ("/v/aaa", "subtitle", true),
+            ("/v/aaa", "no_subtitle", false),
+            ("/v/bbb", "no_subtitle", true),
+            ("/v/zzz", "subtitle", false),
+        ] {
+            let batched = records.iter().find(|r| r["href"] == href);
+            let streamed = find_csv_record(path, "href", href).unwrap();
+            assert_eq!(streamed.as_ref(), batched);
+            assert_eq!(check_torrent_impl(path, href, tt), expected);
+        }
+
+        // A broken row after the match is never read by the streaming path.
+        let mut f = fs::OpenOptions::new().append(true).open(path).unwrap();
+        f.write_all(b"/v/ccc,too,many,fields,here\n").unwrap();
+        assert!(read_csv_records(path).is_err());
+        assert!(check_torrent_impl(path, "/v/aaa", "subtitle"));
+        assert!(find_csv_record(path, "href", "/v/zzz").is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_aggregate_history_statistics() {
+        let dir = std::env::temp_dir();
+        let first = dir.join(format!("history_2023_{}.csv", std::process::id()));
+        let second = dir.join(format!("history_2024_{}.csv", std::process::id()));
+        let missing = dir.join(format!("history_2022_{}.csv", std::process::id()));
+        fs::write(
+            &first,
+            "href,phase,video_code,subtitle,no_subtitle\n\
+             /v/a,1,AAA-001,[2023-01-01]magnet:a,\n\
+             /v/b,2,BBB-002,,[2023-02-01]magnet:b\n",
+        )
+        .unwrap();
+        fs::write(
+            &second,
+            "href,phase,video_code,hacked_subtitle,subtitle\n\
+             /v/b,1,BBB-002,[2024-01-01]magnet:c,[2024-01-01]magnet:d\n",
+        )
+        .unwrap();
+
+        let files: Vec<String> = [&first, &second, &missing]
+            .iter()
+            .map(|p| p.to_str().unwrap().to_string())
+            .collect();
+        let stats = aggregate_history_stats_impl(&files);
+        assert_eq!(stats.total_records, 3);
+        assert_eq!(stats.hrefs.len(), 2);
+        assert_eq!(stats.by_category["subtitle"], 2);
+        assert_eq!(stats.by_category["no_subtitle"], 1);
+        assert_eq!(stats.by_category["hacked_subtitle"], 1);
+        assert_eq!(stats.by_category["hacked_no_subtitle"], 0);
+        assert_eq!(stats.by_phase["1"], 2);
+        assert_eq!(stats.files_read.len(), 2);
+        assert_eq!(stats.missing_files, vec![files[2].clone()]);
+
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+
     #[test]
     fn test_is_downloaded_torrent() {
         assert!(is_downloaded_torrent("[DOWNLOADED PREVIOUSLY]"));
@@ -1199,8 +2060,142 @@ mod tests {
         rec.insert("hacked_no_subtitle".into(), "[2025-01-01]magnet:def".into());
         rec.insert("subtitle".into(), "[2025-01-01]magnet:ghi".into());
         rec.insert("no_subtitle".into(), "[2025-01-01]magnet:jkl".into());
-        apply_priority_cleanup(&mut rec);
+        apply_priority_cleanup(&mut rec, &PriorityPolicy::default());
         assert_eq!(rec["hacked_no_subtitle"], "");
         assert_eq!(rec["no_subtitle"], "");
     }
+
+    #[test]
+    fn test_load_history_merge_duplicates() {
+        let path = std::env::temp_dir().join(format!("history_merge_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let policy = PriorityPolicy::default();
+        let row = |updated: &str, cat: &str, magnet: &str| {
+            let mut rec = Record::new();
+            rec.insert("href".into(), "/v/a".into());
+            rec.insert("phase".into(), "1".into());
+            rec.insert("video_code".into(), "ABC-123".into());
+            rec.insert("update_datetime".into(), updated.into());
+            rec.insert(cat.into(), format!("[{}]{}", &updated[..10], magnet));
+            rec.insert(format!("size_{}", cat), "1.0GB".into());
+            rec
+        };
+        let records = vec![
+            row("2024-01-01 10:00:00", "subtitle", "magnet:?xt=urn:btih:old"),
+            row("2024-02-01 10:00:00", "hacked_subtitle", "magnet:?xt=urn:btih:new"),
+        ];
+
+        write_csv_records(path, &records, true).unwrap();
+        let kept = load_history_impl(path, None, true, false, false, &policy).unwrap();
+        assert_eq!(kept["/v/a"]["torrent_types"], "hacked_subtitle");
+        assert_eq!(kept["/v/a"]["subtitle"], "");
+
+        write_csv_records(path, &records, true).unwrap();
+        let merged = load_history_impl(path, None, true, true, false, &policy).unwrap();
+        let entry = &merged["/v/a"];
+        assert_eq!(entry["torrent_types"], "hacked_subtitle,subtitle");
+        assert_eq!(entry["subtitle"], "[2024-01-01]magnet:?xt=urn:btih:old");
+        assert_eq!(entry["size_subtitle"], "1.0GB");
+        assert_eq!(entry["update_datetime"], "2024-02-01 10:00:00");
+
+        // The cleaned-up file keeps the merged row.
+        let (_headers, on_disk) = read_csv_records(path).unwrap();
+        assert_eq!(on_disk.len(), 1);
+        assert!(on_disk[0]["subtitle"].ends_with("btih:old"));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_load_history_dedup_by_code() {
+        let path = std::env::temp_dir().join(format!("history_bycode_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let policy = PriorityPolicy::default();
+        let row = |href: &str, code: &str, updated: &str, cats: &[&str]| {
+            let mut rec = Record::new();
+            rec.insert("href".into(), href.into());
+            rec.insert("phase".into(), "1".into());
+            rec.insert("video_code".into(), code.into());
+            rec.insert("update_datetime".into(), updated.into());
+            for cat in cats {
+                let magnet = format!("[{}]magnet:?xt=urn:btih:{}", &updated[..10], cat);
+                rec.insert(cat.to_string(), magnet);
+            }
+            rec
+        };
+        let records = vec![
+            row("/v/en", "ABC-123", "2024-01-01 10:00:00", &["hacked_no_subtitle", "subtitle"]),
+            row("/v/ja", "abc123", "2024-02-01 10:00:00", &["hacked_subtitle", "no_subtitle"]),
+            row("/v/other", "XYZ-001", "2024-01-15 10:00:00", &["no_subtitle"]),
+        ];
+
+        write_csv_records(path, &records, true).unwrap();
+        let history = load_history_impl(path, None, true, false, false, &policy).unwrap();
+        assert_eq!(history.len(), 3);
+
+        write_csv_records(path, &records, true).unwrap();
+        let history = load_history_impl(path, None, true, false, true, &policy).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(!history.contains_key("/v/en"));
+        let entry = &history["/v/ja"];
+        // Each href held a different category; the losers of each priority
+        // pair are dropped once the winners are merged in.
+        assert_eq!(entry["torrent_types"], "hacked_subtitle,subtitle");
+        assert_eq!(entry["hacked_no_subtitle"], "");
+        assert_eq!(entry["no_subtitle"], "");
+        assert_eq!(entry["update_datetime"], "2024-02-01 10:00:00");
+        assert_eq!(history["/v/other"]["torrent_types"], "no_subtitle");
+
+        let (_headers, on_disk) = read_csv_records(path).unwrap();
+        assert_eq!(on_disk.len(), 2);
+
+        // With cleanup disabled every merged category survives, on disk too.
+        let keep_all = PriorityPolicy {
+            enabled: false,
+            ..PriorityPolicy::default()
+        };
+        write_csv_records(path, &records, true).unwrap();
+        let history = load_history_impl(path, None, true, false, true, &keep_all).unwrap();
+        assert_eq!(
+            history["/v/ja"]["torrent_types"],
+            "hacked_subtitle,hacked_no_subtitle,subtitle,no_subtitle"
+        );
+        let (_headers, on_disk) = read_csv_records(path).unwrap();
+        let merged = on_disk.iter().find(|r| r["href"] == "/v/ja").unwrap();
+        assert!(merged["hacked_no_subtitle"].ends_with("btih:hacked_no_subtitle"));
+        assert!(merged["no_subtitle"].ends_with("btih:no_subtitle"));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_priority_cleanup_disabled_keeps_both() {
+        let path =
+            std::env::temp_dir().join(format!("history_policy_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = fs::remove_file(path);
+
+        let mut links = HashMap::new();
+        links.insert("subtitle".to_string(), "magnet:?xt=urn:btih:sub".to_string());
+        links.insert("no_subtitle".to_string(), "magnet:?xt=urn:btih:raw".to_string());
+        let policy = PriorityPolicy {
+            enabled: false,
+            ..PriorityPolicy::default()
+        };
+        save_history_impl(path, "/v/a", "1", "ABC-123", &links, &HashMap::new(), &policy, true)
+            .unwrap();
+        let (_headers, records) = read_csv_records(path).unwrap();
+        assert!(records[0]["subtitle"].ends_with("magnet:?xt=urn:btih:sub"));
+        assert!(records[0]["no_subtitle"].ends_with("magnet:?xt=urn:btih:raw"));
+
+        // A custom pair reverses the default precedence.
+        let mut rec = records[0].clone();
+        let reversed = PriorityPolicy {
+            enabled: true,
+            pairs: vec![("no_subtitle".into(), "subtitle".into())],
+        };
+        apply_priority_cleanup(&mut rec, &reversed);
+        assert_eq!(rec["subtitle"], "");
+        assert!(!rec["no_subtitle"].is_empty());
+
+        fs::remove_file(path).unwrap();
+    }
 }
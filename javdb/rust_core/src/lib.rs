@@ -1,4 +1,5 @@
 use pyo3::prelude::*;
+use std::collections::HashMap;
 
 pub mod csv_writer;
 pub mod dedup_ops;
@@ -11,8 +12,9 @@ pub mod scraper;
 pub mod url_helper;
 
 use models::{
-    ActorCredit, CategoryPageResult, IndexPageResult, MagnetInfo, MovieDetail, MovieIndexEntry,
-    MovieLink, TagCategory, TagOption, TagPageResult, TopPageResult,
+    ActorCredit, ActorDetail, ActorIndexEntry, ActorPageResult, CategoryPageResult,
+    IndexPageResult, MagnetInfo, MovieComment, MovieDetail, MovieIndexEntry, MovieLink,
+    ParseWarning, RunReport, TagCategory, TagOption, TagPageResult, TopPageResult,
 };
 use proxy::ban_manager::{get_global_ban_manager, ProxyBanManager};
 use proxy::masking::{
@@ -22,24 +24,62 @@ use proxy::masking::{
 use proxy::pool::{create_proxy_pool_from_config, ProxyInfo, ProxyPool};
 use history::manager::{
     load_parsed_movies_history, cleanup_history_file, maintain_history_limit,
-    save_parsed_movie_to_history, validate_history_file, determine_torrent_types,
+    save_parsed_movie_to_history, remove_from_history, validate_history_file,
+    check_history_schema, aggregate_history_statistics, determine_torrent_types,
     determine_torrent_type, get_missing_torrent_types, has_complete_subtitles,
     should_skip_recent_yesterday_release, should_skip_recent_today_release,
     batch_update_last_visited,
-    should_process_movie, check_torrent_in_history, add_downloaded_indicator_to_csv,
-    is_downloaded_torrent, mark_torrent_as_downloaded,
+    should_process_movie, compute_upgrades, check_torrent_in_history, add_downloaded_indicator_to_csv,
+    is_downloaded_torrent, mark_torrent_as_downloaded, parse_history_tz_offset, PriorityPolicy,
 };
 
 // Python-facing wrapper functions for parsers
 #[pyfunction]
-#[pyo3(signature = (html_content, page_num=1))]
-fn parse_index_page(html_content: &str, page_num: i32) -> IndexPageResult {
-    scraper::index_parser::parse_index_page(html_content, page_num)
+#[pyo3(signature = (html_content, page_num=1, exclude_tags=vec![], include_tags=vec![]))]
+fn parse_index_page(
+    html_content: &str,
+    page_num: i32,
+    exclude_tags: Vec<String>,
+    include_tags: Vec<String>,
+) -> IndexPageResult {
+    let mut result = scraper::index_parser::parse_index_page(html_content, page_num);
+    result.movies =
+        scraper::index_parser::filter_entries_by_tags(result.movies, &include_tags, &exclude_tags);
+    result
+}
+
+#[pyfunction]
+fn collect_tags(result: &IndexPageResult) -> HashMap<String, i32> {
+    scraper::index_parser::collect_tags(result)
+}
+
+#[pyfunction]
+fn dedup_entries(entries: Vec<MovieIndexEntry>) -> Vec<MovieIndexEntry> {
+    scraper::index_parser::dedup_entries(entries)
+}
+
+#[pyfunction]
+#[pyo3(signature = (html_content, max_fanart=None, dayone_window_days=scraper::detail_parser::DEFAULT_DAYONE_WINDOW_DAYS))]
+fn parse_detail_page(
+    html_content: &str,
+    max_fanart: Option<usize>,
+    dayone_window_days: i64,
+) -> MovieDetail {
+    let mut detail = scraper::detail_parser::parse_detail_page(html_content);
+    scraper::detail_parser::limit_fanart(&mut detail, max_fanart);
+    scraper::detail_parser::mark_dayone_magnet(&mut detail, dayone_window_days);
+    detail
+}
+
+#[pyfunction]
+fn parse_detail_page_diagnostic(html_content: &str) -> (MovieDetail, HashMap<String, bool>) {
+    scraper::detail_parser::parse_detail_page_diagnostic(html_content)
 }
 
 #[pyfunction]
-fn parse_detail_page(html_content: &str) -> MovieDetail {
-    scraper::detail_parser::parse_detail_page(html_content)
+#[pyo3(signature = (detail, required=None))]
+fn detail_completeness(detail: &MovieDetail, required: Option<Vec<String>>) -> HashMap<String, bool> {
+    scraper::detail_parser::detail_completeness(detail, required.as_deref())
 }
 
 #[pyfunction]
@@ -54,6 +94,17 @@ fn parse_top_page(html_content: &str, page_num: i32) -> TopPageResult {
     scraper::index_parser::parse_top_page(html_content, page_num)
 }
 
+#[pyfunction]
+#[pyo3(signature = (html_content, page_num=1))]
+fn parse_actor_page(html_content: &str, page_num: i32) -> ActorPageResult {
+    scraper::index_parser::parse_actor_page(html_content, page_num)
+}
+
+#[pyfunction]
+fn parse_actor_detail(html_content: &str) -> ActorDetail {
+    scraper::actor_parser::parse_actor_detail(html_content)
+}
+
 #[pyfunction]
 #[pyo3(signature = (html_content, page_num=1))]
 fn parse_tag_page(html_content: &str, page_num: i32) -> TagPageResult {
@@ -65,11 +116,51 @@ fn detect_page_type(html_content: &str) -> String {
     scraper::common::detect_page_type(html_content)
 }
 
+#[pyfunction]
+fn detect_locale(html_content: &str) -> String {
+    scraper::common::detect_locale(html_content)
+}
+
 #[pyfunction]
 fn is_login_page(html_content: &str) -> bool {
     scraper::common::is_login_page(html_content)
 }
 
+#[pyfunction]
+fn extract_session_validity(html_content: &str) -> bool {
+    scraper::common::extract_session_validity(html_content)
+}
+
+#[pyfunction]
+fn is_removed_title(html_content: &str) -> bool {
+    scraper::common::is_removed_title(html_content)
+}
+
+#[pyfunction]
+fn is_cf_access_denied(html_content: &str) -> bool {
+    scraper::common::is_cf_access_denied(html_content)
+}
+
+#[pyfunction]
+fn classify_response(html_content: &str) -> String {
+    scraper::common::classify_response(html_content)
+}
+
+#[pyfunction]
+fn resolve_url(base: &str, href: &str) -> String {
+    scraper::common::resolve_url(base, href)
+}
+
+#[pyfunction]
+fn normalize_video_code(code: &str) -> String {
+    scraper::common::normalize_video_code(code)
+}
+
+#[pyfunction]
+fn video_codes_equal(a: &str, b: &str) -> bool {
+    scraper::common::video_codes_equal(a, b)
+}
+
 #[pyfunction]
 fn validate_index_html(html_content: &str) -> (bool, bool) {
     scraper::common::validate_index_html(html_content)
@@ -84,14 +175,20 @@ fn rust_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<MovieLink>()?;
     m.add_class::<ActorCredit>()?;
     m.add_class::<MagnetInfo>()?;
+    m.add_class::<MovieComment>()?;
+    m.add_class::<ParseWarning>()?;
     m.add_class::<MovieIndexEntry>()?;
     m.add_class::<MovieDetail>()?;
     m.add_class::<IndexPageResult>()?;
     m.add_class::<CategoryPageResult>()?;
     m.add_class::<TopPageResult>()?;
+    m.add_class::<ActorIndexEntry>()?;
+    m.add_class::<ActorPageResult>()?;
+    m.add_class::<ActorDetail>()?;
     m.add_class::<TagOption>()?;
     m.add_class::<TagCategory>()?;
     m.add_class::<TagPageResult>()?;
+    m.add_class::<RunReport>()?;
 
     // --- Proxy ---
     m.add_class::<ProxyInfo>()?;
@@ -112,20 +209,38 @@ fn rust_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     // --- Parsers ---
     m.add_function(wrap_pyfunction!(parse_index_page, m)?)?;
+    m.add_function(wrap_pyfunction!(dedup_entries, m)?)?;
+    m.add_function(wrap_pyfunction!(collect_tags, m)?)?;
     m.add_function(wrap_pyfunction!(parse_detail_page, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_detail_page_diagnostic, m)?)?;
+    m.add_function(wrap_pyfunction!(detail_completeness, m)?)?;
     m.add_function(wrap_pyfunction!(parse_category_page, m)?)?;
     m.add_function(wrap_pyfunction!(parse_top_page, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_actor_page, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_actor_detail, m)?)?;
     m.add_function(wrap_pyfunction!(parse_tag_page, m)?)?;
     m.add_function(wrap_pyfunction!(detect_page_type, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_locale, m)?)?;
     m.add_function(wrap_pyfunction!(is_login_page, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_session_validity, m)?)?;
     m.add_function(wrap_pyfunction!(validate_index_html, m)?)?;
+    m.add_function(wrap_pyfunction!(is_removed_title, m)?)?;
+    m.add_function(wrap_pyfunction!(is_cf_access_denied, m)?)?;
+    m.add_function(wrap_pyfunction!(classify_response, m)?)?;
+    m.add_function(wrap_pyfunction!(resolve_url, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_video_code, m)?)?;
+    m.add_function(wrap_pyfunction!(video_codes_equal, m)?)?;
 
     // --- History Manager ---
+    m.add_class::<PriorityPolicy>()?;
     m.add_function(wrap_pyfunction!(load_parsed_movies_history, m)?)?;
     m.add_function(wrap_pyfunction!(cleanup_history_file, m)?)?;
     m.add_function(wrap_pyfunction!(maintain_history_limit, m)?)?;
     m.add_function(wrap_pyfunction!(save_parsed_movie_to_history, m)?)?;
+    m.add_function(wrap_pyfunction!(remove_from_history, m)?)?;
     m.add_function(wrap_pyfunction!(validate_history_file, m)?)?;
+    m.add_function(wrap_pyfunction!(check_history_schema, m)?)?;
+    m.add_function(wrap_pyfunction!(aggregate_history_statistics, m)?)?;
     m.add_function(wrap_pyfunction!(determine_torrent_types, m)?)?;
     m.add_function(wrap_pyfunction!(determine_torrent_type, m)?)?;
     m.add_function(wrap_pyfunction!(get_missing_torrent_types, m)?)?;
@@ -134,10 +249,12 @@ fn rust_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(should_skip_recent_today_release, m)?)?;
     m.add_function(wrap_pyfunction!(batch_update_last_visited, m)?)?;
     m.add_function(wrap_pyfunction!(should_process_movie, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_upgrades, m)?)?;
     m.add_function(wrap_pyfunction!(check_torrent_in_history, m)?)?;
     m.add_function(wrap_pyfunction!(add_downloaded_indicator_to_csv, m)?)?;
     m.add_function(wrap_pyfunction!(is_downloaded_torrent, m)?)?;
     m.add_function(wrap_pyfunction!(mark_torrent_as_downloaded, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_history_tz_offset, m)?)?;
 
     // --- CSV Writer ---
     m.add_function(wrap_pyfunction!(csv_writer::merge_row_data, m)?)?;
@@ -153,9 +270,12 @@ fn rust_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(url_helper::get_page_url, m)?)?;
     m.add_function(wrap_pyfunction!(url_helper::sanitize_filename_part, m)?)?;
     m.add_function(wrap_pyfunction!(url_helper::extract_url_part_after_javdb, m)?)?;
+    m.add_function(wrap_pyfunction!(url_helper::build_movie_filename, m)?)?;
+    m.add_function(wrap_pyfunction!(url_helper::build_dedup_key, m)?)?;
 
     // --- Magnet Extractor ---
     m.add_function(wrap_pyfunction!(magnet_extractor::extract_magnets, m)?)?;
+    m.add_function(wrap_pyfunction!(magnet_extractor::merge_magnets_with_history, m)?)?;
 
     // --- RClone Ops ---
     m.add_function(wrap_pyfunction!(rclone_ops::parse_folder_name, m)?)?;
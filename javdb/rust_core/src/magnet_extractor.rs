@@ -1,18 +1,56 @@
+use once_cell::sync::Lazy;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
 use std::collections::HashMap;
 
-#[derive(FromPyObject, Clone)]
+use crate::models::MagnetInfo;
+use crate::scraper::detail_parser::parse_magnets_fragment;
+
+const DOWNLOADED_PLACEHOLDER: &str = "[DOWNLOADED PREVIOUSLY]";
+
+static NAME_4K_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)4k|2160p|\buhd\b").unwrap());
+static NAME_VR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bvr\b").unwrap());
+static NAME_UNCENSORED_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)无码|無碼|uncensored").unwrap());
+static NAME_LEAKED_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)流出|破解|leak").unwrap());
+static NAME_SUBTITLE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)中文字幕|中字|-C(?:\.|$)").unwrap());
+
+#[derive(Clone)]
 pub struct MagnetInput {
-    #[pyo3(item)]
     href: String,
-    #[pyo3(item)]
     name: String,
-    #[pyo3(item)]
     tags: Vec<String>,
-    #[pyo3(item)]
     size: String,
-    #[pyo3(item)]
     timestamp: String,
+    is_recommended: bool,
+    tracker_count: i32,
+}
+
+// Hand-written rather than derived so ``is_recommended`` and
+// ``tracker_count`` can be optional: magnet dicts from the Python fallback
+// parser do not carry them.
+impl<'py> FromPyObject<'py> for MagnetInput {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let is_recommended = match ob.get_item("is_recommended") {
+            Ok(v) => v.extract()?,
+            Err(_) => false,
+        };
+        let tracker_count = match ob.get_item("tracker_count") {
+            Ok(v) => v.extract()?,
+            Err(_) => 0,
+        };
+        Ok(Self {
+            href: ob.get_item("href")?.extract()?,
+            name: ob.get_item("name")?.extract()?,
+            tags: ob.get_item("tags")?.extract()?,
+            size: ob.get_item("size")?.extract()?,
+            timestamp: ob.get_item("timestamp")?.extract()?,
+            is_recommended,
+            tracker_count,
+        })
+    }
 }
 
 fn parse_size(size_str: &str) -> f64 {
@@ -31,8 +69,10 @@ fn parse_size(size_str: &str) -> f64 {
     }
 }
 
-fn sort_key(m: &MagnetInput) -> (String, i64) {
-    (m.timestamp.clone(), parse_size(&m.size) as i64)
+/// Recommended (site-highlighted) magnets rank first, then newest, then the
+/// one announcing more trackers, then largest.
+fn sort_key(m: &MagnetInput) -> (bool, String, i32, i64) {
+    (m.is_recommended, m.timestamp.clone(), m.tracker_count, parse_size(&m.size) as i64)
 }
 
 fn sort_magnets(magnets: &mut [MagnetInput]) {
@@ -142,3 +182,207 @@ pub fn extract_magnets(magnets: Vec<MagnetInput>) -> HashMap<String, String> {
 
     result
 }
+
+/// Torrent category of a single magnet, using the same name / tag rules
+/// as ``extract_magnets``.
+fn classify_magnet(name: &str, tags: &[String]) -> &'static str {
+    if is_hacked_subtitle(name) {
+        "hacked_subtitle"
+    } else if is_hacked_no_subtitle(name) {
+        "hacked_no_subtitle"
+    } else if has_subtitle_tag(tags) {
+        "subtitle"
+    } else {
+        "no_subtitle"
+    }
+}
+
+/// Quality booleans for one magnet from its tags and file name:
+/// ``is_4k``, ``is_uncensored``, ``has_subtitle``, ``is_vr``, ``is_leaked``.
+/// Cracked releases (``-U`` / ``无码破解``) count as uncensored and leaked.
+pub fn quality_flags(name: &str, tags: &[String]) -> HashMap<String, bool> {
+    let tag_has = |needles: &[&str]| {
+        tags.iter().any(|t| {
+            let t = t.to_lowercase();
+            needles.iter().any(|n| t.contains(n))
+        })
+    };
+    let hacked = is_hacked(name);
+    let flags = [
+        ("is_4k", tag_has(&["4k"]) || NAME_4K_RE.is_match(name)),
+        (
+            "is_uncensored",
+            hacked || tag_has(&["无码", "無碼", "uncensored"]) || NAME_UNCENSORED_RE.is_match(name),
+        ),
+        (
+            "has_subtitle",
+            has_subtitle_tag(tags) || is_hacked_subtitle(name) || NAME_SUBTITLE_RE.is_match(name),
+        ),
+        ("is_vr", tag_has(&["vr"]) || NAME_VR_RE.is_match(name)),
+        (
+            "is_leaked",
+            hacked || tag_has(&["流出", "leak"]) || NAME_LEAKED_RE.is_match(name),
+        ),
+    ];
+    flags.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+}
+
+fn merge_magnets_impl(magnets_html: &str, downloaded_types: &[String]) -> Vec<MagnetInfo> {
+    let mut magnets = parse_magnets_fragment(magnets_html);
+    for m in &mut magnets {
+        m.category = classify_magnet(&m.name, &m.tags).to_string();
+        m.downloaded = downloaded_types.contains(&m.category);
+    }
+    magnets
+}
+
+/// Categories a history entry (as returned by ``load_parsed_movies_history``)
+/// already holds: its ``torrent_types`` plus any category column carrying
+/// the ``[DOWNLOADED PREVIOUSLY]`` marker.
+fn downloaded_types_from_entry(entry: &Bound<'_, PyDict>) -> PyResult<Vec<String>> {
+    let mut types: Vec<String> = match entry.get_item("torrent_types")? {
+        Some(v) => match v.extract::<Vec<String>>() {
+            Ok(list) => list,
+            Err(_) => v
+                .extract::<String>()?
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        },
+        None => Vec::new(),
+    };
+    for cat in ["hacked_subtitle", "hacked_no_subtitle", "subtitle", "no_subtitle"] {
+        if let Some(v) = entry.get_item(cat)? {
+            let content: String = v.extract().unwrap_or_default();
+            let marked = content.trim().starts_with(DOWNLOADED_PLACEHOLDER);
+            if marked && !types.iter().any(|t| t == cat) {
+                types.push(cat.to_string());
+            }
+        }
+    }
+    Ok(types)
+}
+
+/// Parse a magnet list fragment, classify each magnet and flag the ones
+/// whose category the movie's history entry already has.
+#[pyfunction]
+#[pyo3(signature = (magnets_html, history_entry=None))]
+pub fn merge_magnets_with_history(
+    magnets_html: &str,
+    history_entry: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<MagnetInfo>> {
+    let downloaded = match history_entry {
+        Some(entry) => downloaded_types_from_entry(entry)?,
+        None => Vec::new(),
+    };
+    Ok(merge_magnets_impl(magnets_html, &downloaded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_quality_flags_from_tags() {
+        let flags = quality_flags("ABC-123", &tags(&["高清", "字幕"]));
+        assert!(flags["has_subtitle"]);
+        assert!(
+            !flags["is_4k"] && !flags["is_uncensored"] && !flags["is_vr"] && !flags["is_leaked"]
+        );
+
+        let flags = quality_flags("ABC-123", &tags(&["4K", "無碼", "VR", "流出"]));
+        assert!(flags["is_4k"] && flags["is_uncensored"] && flags["is_vr"] && flags["is_leaked"]);
+        assert!(!flags["has_subtitle"]);
+    }
+
+    #[test]
+    fn test_quality_flags_from_filename() {
+        let flags = quality_flags("ABC-123.2160p.mp4", &[]);
+        assert!(flags["is_4k"]);
+        assert!(!flags["has_subtitle"]);
+
+        let flags = quality_flags("ABC-123-UC.无码破解", &[]);
+        assert!(flags["is_uncensored"] && flags["is_leaked"] && flags["has_subtitle"]);
+
+        let flags = quality_flags("ABC-123-C", &[]);
+        assert!(flags["has_subtitle"] && !flags["is_uncensored"]);
+
+        let flags = quality_flags("KAVR-123 VR 8K", &[]);
+        assert!(flags["is_vr"]);
+        assert!(!quality_flags("VRTM-123", &[])["is_vr"]);
+    }
+
+    fn magnet(href: &str, timestamp: &str, size: &str, is_recommended: bool) -> MagnetInput {
+        MagnetInput {
+            href: href.to_string(),
+            name: "ABC-123".to_string(),
+            tags: Vec::new(),
+            size: size.to_string(),
+            timestamp: timestamp.to_string(),
+            is_recommended,
+            tracker_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_recommended_magnet_ranks_first() {
+        let mut magnets = vec![
+            magnet("magnet:newest", "2024-06-01", "6.0GB", false),
+            magnet("magnet:pinned", "2023-01-01", "4.0GB", true),
+            magnet("magnet:older", "2024-01-01", "8.0GB", false),
+        ];
+        assert_eq!(best_from(&mut magnets).unwrap().href, "magnet:pinned");
+
+        magnets.retain(|m| !m.is_recommended);
+        assert_eq!(best_from(&mut magnets).unwrap().href, "magnet:newest");
+    }
+
+    #[test]
+    fn test_tracker_count_breaks_date_ties() {
+        let mut tracked = magnet("magnet:tracked", "2024-06-01", "4.0GB", false);
+        tracked.tracker_count = 5;
+        let mut magnets = vec![
+            magnet("magnet:bare", "2024-06-01", "8.0GB", false),
+            tracked,
+            magnet("magnet:older", "2024-01-01", "9.0GB", false),
+        ];
+        assert_eq!(best_from(&mut magnets).unwrap().href, "magnet:tracked");
+    }
+
+    #[test]
+    fn test_merge_magnets_marks_downloaded_category() {
+        let html = r#"<div id="magnets-content">
+            <div class="item columns is-desktop">
+              <div class="magnet-name column is-four-fifths">
+                <a href="magnet:?xt=urn:btih:sub"><span class="name">ABC-123-C</span>
+                  <div class="tags"><span class="tag">字幕</span></div></a>
+              </div>
+            </div>
+            <div class="item columns is-desktop odd">
+              <div class="magnet-name column is-four-fifths">
+                <a href="magnet:?xt=urn:btih:plain"><span class="name">ABC-123</span></a>
+              </div>
+            </div>
+            <div class="item columns is-desktop">
+              <div class="magnet-name column is-four-fifths">
+                <a href="magnet:?xt=urn:btih:uc"><span class="name">ABC-123-UC</span></a>
+              </div>
+            </div>
+        </div>"#;
+        let merged = merge_magnets_impl(html, &["subtitle".to_string()]);
+        let summary: Vec<(&str, bool)> =
+            merged.iter().map(|m| (m.category.as_str(), m.downloaded)).collect();
+        assert_eq!(
+            summary,
+            [("subtitle", true), ("no_subtitle", false), ("hacked_subtitle", false)]
+        );
+
+        let fresh = merge_magnets_impl(html, &[]);
+        assert!(fresh.iter().all(|m| !m.downloaded));
+    }
+}
@@ -7,6 +7,21 @@ fn new_dict(py: Python<'_>) -> Bound<'_, PyDict> {
     PyDict::new_bound(py)
 }
 
+/// ``serde_json`` encoding shared by the ``to_json`` methods; ``Option``
+/// fields come out as ``null``.
+fn to_json_string<T: Serialize>(value: &T) -> PyResult<String> {
+    serde_json::to_string(value)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("json encode: {e}")))
+}
+
+fn compact_dict<'py>(py: Python<'py>, fields: &[(&str, &str)]) -> PyResult<Bound<'py, PyDict>> {
+    let dict = new_dict(py);
+    for (key, value) in fields {
+        dict.set_item(key, value)?;
+    }
+    Ok(dict)
+}
+
 // ---------------------------------------------------------------------------
 // MovieLink
 // ---------------------------------------------------------------------------
@@ -18,20 +33,29 @@ pub struct MovieLink {
     pub name: String,
     #[pyo3(get, set)]
     pub href: String,
+    /// Title count shown next to maker / publisher / series links, if any.
+    #[pyo3(get, set)]
+    #[serde(default)]
+    pub work_count: Option<i32>,
 }
 
 #[pymethods]
 impl MovieLink {
     #[new]
-    #[pyo3(signature = (name, href))]
-    fn new(name: String, href: String) -> Self {
-        Self { name, href }
+    #[pyo3(signature = (name, href, work_count=None))]
+    fn new(name: String, href: String, work_count: Option<i32>) -> Self {
+        Self {
+            name,
+            href,
+            work_count,
+        }
     }
 
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("name", &self.name)?;
         dict.set_item("href", &self.href)?;
+        dict.set_item("work_count", self.work_count)?;
         Ok(dict)
     }
 
@@ -53,14 +77,23 @@ pub struct ActorCredit {
     pub href: String,
     #[pyo3(get, set)]
     pub gender: String,
+    /// Role label next to the actor (``主演`` / ``客串``), when the page shows one.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub role: Option<String>,
 }
 
 #[pymethods]
 impl ActorCredit {
     #[new]
-    #[pyo3(signature = (name, href, gender=String::new()))]
-    fn new(name: String, href: String, gender: String) -> Self {
-        Self { name, href, gender }
+    #[pyo3(signature = (name, href, gender=String::new(), role=None))]
+    fn new(name: String, href: String, gender: String, role: Option<String>) -> Self {
+        Self {
+            name,
+            href,
+            gender,
+            role,
+        }
     }
 
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
@@ -68,6 +101,7 @@ impl ActorCredit {
         dict.set_item("name", &self.name)?;
         dict.set_item("href", &self.href)?;
         dict.set_item("gender", &self.gender)?;
+        dict.set_item("role", &self.role)?;
         Ok(dict)
     }
 
@@ -79,6 +113,107 @@ impl ActorCredit {
     }
 }
 
+// ---------------------------------------------------------------------------
+// MovieComment (short review from the detail page review tab)
+// ---------------------------------------------------------------------------
+
+#[pyclass(name = "RustMovieComment")]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MovieComment {
+    #[pyo3(get, set)]
+    pub author: String,
+    /// Star score (0–5) the reviewer gave; ``None`` when no stars are shown.
+    #[pyo3(get, set)]
+    pub score: Option<i32>,
+    #[pyo3(get, set)]
+    pub date: String,
+    #[pyo3(get, set)]
+    pub content: String,
+    #[pyo3(get, set)]
+    pub helpful_count: i32,
+}
+
+#[pymethods]
+impl MovieComment {
+    #[new]
+    #[pyo3(signature = (author=String::new(), score=None, date=String::new(), content=String::new(), helpful_count=0))]
+    fn new(
+        author: String,
+        score: Option<i32>,
+        date: String,
+        content: String,
+        helpful_count: i32,
+    ) -> Self {
+        Self {
+            author,
+            score,
+            date,
+            content,
+            helpful_count,
+        }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = new_dict(py);
+        dict.set_item("author", &self.author)?;
+        dict.set_item("score", self.score)?;
+        dict.set_item("date", &self.date)?;
+        dict.set_item("content", &self.content)?;
+        dict.set_item("helpful_count", self.helpful_count)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RustMovieComment(author='{}', score={:?})",
+            self.author, self.score
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ParseWarning (machine-readable parser problem, e.g. a missing section)
+// ---------------------------------------------------------------------------
+
+#[pyclass(name = "RustParseWarning")]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParseWarning {
+    /// Stable identifier such as ``"magnets_missing"``, for alerting.
+    #[pyo3(get, set)]
+    pub code: String,
+    #[pyo3(get, set)]
+    pub message: String,
+}
+
+impl ParseWarning {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[pymethods]
+impl ParseWarning {
+    #[new]
+    #[pyo3(signature = (code=String::new(), message=String::new()))]
+    fn py_new(code: String, message: String) -> Self {
+        Self { code, message }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = new_dict(py);
+        dict.set_item("code", &self.code)?;
+        dict.set_item("message", &self.message)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RustParseWarning(code='{}', message='{}')", self.code, self.message)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // MagnetInfo
 // ---------------------------------------------------------------------------
@@ -98,12 +233,42 @@ pub struct MagnetInfo {
     pub file_count: u32,
     #[pyo3(get, set)]
     pub timestamp: String,
+    /// Number of ``tr=`` trackers in the magnet URI (availability hint).
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub tracker_count: i32,
+    /// Seed / peer counts when the meta span shows them; usually ``None``.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub seeders: Option<i32>,
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub leechers: Option<i32>,
+    /// Site-supplied "高清" badge, independent of any resolution in the name.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub is_hd: bool,
+    /// Highlighted ("recommended") by the site; ranked first by ``extract_magnets``.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub is_recommended: bool,
+    /// Torrent category (``subtitle``, ``hacked_no_subtitle``, ...); empty
+    /// until classified by ``merge_magnets_with_history``.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub category: String,
+    /// The history already records this category for the movie, i.e. the
+    /// CSV would show ``[DOWNLOADED PREVIOUSLY]`` for it.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub downloaded: bool,
 }
 
 #[pymethods]
 impl MagnetInfo {
     #[new]
-    #[pyo3(signature = (href, name, tags=vec![], size=String::new(), file_count=0, timestamp=String::new()))]
+    #[pyo3(signature = (href, name, tags=vec![], size=String::new(), file_count=0, timestamp=String::new(), tracker_count=0, is_hd=false, is_recommended=false, category=String::new(), downloaded=false, seeders=None, leechers=None))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         href: String,
         name: String,
@@ -111,6 +276,13 @@ impl MagnetInfo {
         size: String,
         file_count: u32,
         timestamp: String,
+        tracker_count: i32,
+        is_hd: bool,
+        is_recommended: bool,
+        category: String,
+        downloaded: bool,
+        seeders: Option<i32>,
+        leechers: Option<i32>,
     ) -> Self {
         Self {
             href,
@@ -119,9 +291,20 @@ impl MagnetInfo {
             size,
             file_count,
             timestamp,
+            tracker_count,
+            seeders,
+            leechers,
+            is_hd,
+            is_recommended,
+            category,
+            downloaded,
         }
     }
 
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("href", &self.href)?;
@@ -130,9 +313,22 @@ impl MagnetInfo {
         dict.set_item("size", &self.size)?;
         dict.set_item("file_count", self.file_count)?;
         dict.set_item("timestamp", &self.timestamp)?;
+        dict.set_item("tracker_count", self.tracker_count)?;
+        dict.set_item("seeders", self.seeders)?;
+        dict.set_item("leechers", self.leechers)?;
+        dict.set_item("is_hd", self.is_hd)?;
+        dict.set_item("is_recommended", self.is_recommended)?;
+        dict.set_item("category", &self.category)?;
+        dict.set_item("downloaded", self.downloaded)?;
         Ok(dict)
     }
 
+    /// Normalized quality booleans (``is_4k``, ``is_uncensored``,
+    /// ``has_subtitle``, ``is_vr``, ``is_leaked``) from ``tags`` and ``name``.
+    pub fn quality_flags(&self) -> HashMap<String, bool> {
+        crate::magnet_extractor::quality_flags(&self.name, &self.tags)
+    }
+
     fn __repr__(&self) -> String {
         format!("RustMagnetInfo(name='{}', size='{}')", self.name, self.size)
     }
@@ -153,6 +349,10 @@ pub struct MovieIndexEntry {
     pub title: String,
     #[pyo3(get, set)]
     pub rate: String,
+    /// ``rate`` as a number in 0–5; ``None`` when absent or out of range.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub rate_value: Option<f64>,
     #[pyo3(get, set)]
     pub comment_count: String,
     #[pyo3(get, set)]
@@ -165,12 +365,15 @@ pub struct MovieIndexEntry {
     pub page: i32,
     #[pyo3(get, set)]
     pub ranking: Option<i32>,
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub disc_number: Option<i32>,
 }
 
 #[pymethods]
 impl MovieIndexEntry {
     #[new]
-    #[pyo3(signature = (href, video_code, title=String::new(), rate=String::new(), comment_count=String::new(), release_date=String::new(), tags=vec![], cover_url=String::new(), page=1, ranking=None))]
+    #[pyo3(signature = (href, video_code, title=String::new(), rate=String::new(), comment_count=String::new(), release_date=String::new(), tags=vec![], cover_url=String::new(), page=1, ranking=None, disc_number=None, rate_value=None))]
     #[allow(clippy::too_many_arguments)]
     fn new(
         href: String,
@@ -183,36 +386,52 @@ impl MovieIndexEntry {
         cover_url: String,
         page: i32,
         ranking: Option<i32>,
+        disc_number: Option<i32>,
+        rate_value: Option<f64>,
     ) -> Self {
         Self {
             href,
             video_code,
             title,
             rate,
+            rate_value,
             comment_count,
             release_date,
             tags,
             cover_url,
             page,
             ranking,
+            disc_number,
         }
     }
 
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("href", &self.href)?;
         dict.set_item("video_code", &self.video_code)?;
         dict.set_item("title", &self.title)?;
         dict.set_item("rate", &self.rate)?;
+        dict.set_item("rate_value", self.rate_value)?;
         dict.set_item("comment_count", &self.comment_count)?;
         dict.set_item("release_date", &self.release_date)?;
         dict.set_item("tags", &self.tags)?;
         dict.set_item("cover_url", &self.cover_url)?;
         dict.set_item("page", self.page)?;
         dict.set_item("ranking", self.ranking)?;
+        dict.set_item("disc_number", self.disc_number)?;
         Ok(dict)
     }
 
+    /// List-view payload with short keys: ``c`` code, ``t`` title, ``r`` rate,
+    /// ``i`` cover image, ``h`` href.
+    fn to_compact_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        compact_dict(py, &self.compact_fields())
+    }
+
     fn to_legacy_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("href", &self.href)?;
@@ -232,6 +451,18 @@ impl MovieIndexEntry {
     }
 }
 
+impl MovieIndexEntry {
+    fn compact_fields(&self) -> [(&'static str, &str); 5] {
+        [
+            ("c", &self.video_code),
+            ("t", &self.title),
+            ("r", &self.rate),
+            ("i", &self.cover_url),
+            ("h", &self.href),
+        ]
+    }
+}
+
 // ---------------------------------------------------------------------------
 // MovieDetail
 // ---------------------------------------------------------------------------
@@ -248,30 +479,59 @@ pub struct MovieDetail {
     pub title: String,
     #[pyo3(get, set)]
     pub video_code: String,
+    /// Numeric JavDB database id from the page's data attributes, if present.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub db_id: Option<String>,
     #[pyo3(get, set)]
     pub code_prefix_link: String,
     #[pyo3(get, set)]
     pub duration: String,
     #[pyo3(get, set)]
     pub release_date: String,
+    /// Uncensored leak date when the page labels one; usually ``None``.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub leak_date: Option<String>,
     #[pyo3(get, set)]
     pub publisher: Option<MovieLink>,
     #[pyo3(get, set)]
     pub maker: Option<MovieLink>,
     #[pyo3(get, set)]
     pub series: Option<MovieLink>,
+    /// Adjacent episodes from the series navigation; ``None`` when absent.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub prev_link: Option<MovieLink>,
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub next_link: Option<MovieLink>,
     #[pyo3(get, set)]
     pub directors: Vec<MovieLink>,
     #[pyo3(get, set)]
     pub tags: Vec<MovieLink>,
     #[pyo3(get, set)]
     pub rate: String,
+    /// ``rate`` as a number in 0–5; ``None`` when absent or out of range.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub rate_value: Option<f64>,
+    /// Scores imported from other sites, keyed by source label (e.g. "FANZA").
+    /// Empty unless the page renders more than the JavDB score.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub external_rates: HashMap<String, String>,
     #[pyo3(get, set)]
     pub comment_count: String,
     #[pyo3(get, set)]
     pub poster_url: String,
     #[pyo3(get, set)]
     pub fanart_urls: Vec<String>,
+    /// Number of sample images on the page, even when ``fanart_urls`` was
+    /// capped with ``max_fanart``.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub sample_image_count: usize,
     #[pyo3(get, set)]
     pub trailer_url: Option<String>,
     #[pyo3(get, set)]
@@ -281,14 +541,36 @@ pub struct MovieDetail {
     pub no_actor_listing: bool,
     #[pyo3(get, set)]
     pub magnets: Vec<MagnetInfo>,
+    /// Server-side magnet order from the active sort tab ("date" / "size").
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub magnets_sort: Option<String>,
+    /// Set only when ``magnets`` is empty: "no_section", "empty" or "request_prompt".
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub magnets_unavailable_reason: Option<String>,
+    /// Some magnet is dated within the day-one window of ``release_date``
+    /// (3 days unless ``parse_detail_page`` is given another window).
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub has_dayone_magnet: bool,
     #[pyo3(get, set)]
     pub review_count: i32,
+    /// Short reviews rendered on the page; empty when the review tab is
+    /// lazy-loaded and not part of the fetched HTML.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub comments: Vec<MovieComment>,
     #[pyo3(get, set)]
     pub want_count: i32,
     #[pyo3(get, set)]
     pub watched_count: i32,
     #[pyo3(get, set)]
     pub parse_success: bool,
+    /// Problems the parser noticed, e.g. no magnets section on the page.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub warnings: Vec<ParseWarning>,
 }
 
 impl Default for MovieDetail {
@@ -296,26 +578,38 @@ impl Default for MovieDetail {
         Self {
             title: String::new(),
             video_code: String::new(),
+            db_id: None,
             code_prefix_link: String::new(),
             duration: String::new(),
             release_date: String::new(),
+            leak_date: None,
             publisher: None,
             maker: None,
             series: None,
+            prev_link: None,
+            next_link: None,
             directors: Vec::new(),
             tags: Vec::new(),
             rate: String::new(),
+            rate_value: None,
+            external_rates: HashMap::new(),
             comment_count: String::new(),
             poster_url: String::new(),
             fanart_urls: Vec::new(),
+            sample_image_count: 0,
             trailer_url: None,
             actors: Vec::new(),
             no_actor_listing: false,
             magnets: Vec::new(),
+            magnets_sort: None,
+            magnets_unavailable_reason: None,
+            has_dayone_magnet: false,
             review_count: 0,
+            comments: Vec::new(),
             want_count: 0,
             watched_count: 0,
             parse_success: true,
+            warnings: Vec::new(),
         }
     }
 }
@@ -328,13 +622,32 @@ impl MovieDetail {
         Self::default()
     }
 
+    /// Short-key summary matching ``RustMovieIndexEntry.to_compact_dict``
+    /// (``i`` is the poster); details carry no href of their own.
+    fn to_compact_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        compact_dict(py, &self.compact_fields())
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
+    /// Rebuild a detail from ``to_json`` output, e.g. a cached page.
+    #[staticmethod]
+    fn from_json(s: &str) -> PyResult<Self> {
+        serde_json::from_str(s)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("json decode: {e}")))
+    }
+
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("title", &self.title)?;
         dict.set_item("video_code", &self.video_code)?;
+        dict.set_item("db_id", &self.db_id)?;
         dict.set_item("code_prefix_link", &self.code_prefix_link)?;
         dict.set_item("duration", &self.duration)?;
         dict.set_item("release_date", &self.release_date)?;
+        dict.set_item("leak_date", &self.leak_date)?;
 
         let pub_dict = self.publisher.as_ref().map(|p| p.to_dict(py)).transpose()?;
         dict.set_item("publisher", pub_dict)?;
@@ -345,6 +658,12 @@ impl MovieDetail {
         let series_dict = self.series.as_ref().map(|s| s.to_dict(py)).transpose()?;
         dict.set_item("series", series_dict)?;
 
+        let prev_dict = self.prev_link.as_ref().map(|l| l.to_dict(py)).transpose()?;
+        dict.set_item("prev_link", prev_dict)?;
+
+        let next_dict = self.next_link.as_ref().map(|l| l.to_dict(py)).transpose()?;
+        dict.set_item("next_link", next_dict)?;
+
         let dirs: Vec<_> = self
             .directors
             .iter()
@@ -360,9 +679,12 @@ impl MovieDetail {
         dict.set_item("tags", tag_dicts)?;
 
         dict.set_item("rate", &self.rate)?;
+        dict.set_item("rate_value", self.rate_value)?;
+        dict.set_item("external_rates", &self.external_rates)?;
         dict.set_item("comment_count", &self.comment_count)?;
         dict.set_item("poster_url", &self.poster_url)?;
         dict.set_item("fanart_urls", &self.fanart_urls)?;
+        dict.set_item("sample_image_count", self.sample_image_count)?;
         dict.set_item("trailer_url", &self.trailer_url)?;
 
         let actor_dicts: Vec<_> = self
@@ -400,11 +722,26 @@ impl MovieDetail {
             .map(|m| m.to_dict(py))
             .collect::<Result<_, _>>()?;
         dict.set_item("magnets", magnet_dicts)?;
+        dict.set_item("magnets_sort", &self.magnets_sort)?;
+        dict.set_item("magnets_unavailable_reason", &self.magnets_unavailable_reason)?;
+        dict.set_item("has_dayone_magnet", self.has_dayone_magnet)?;
 
         dict.set_item("review_count", self.review_count)?;
+        let comment_dicts: Vec<_> = self
+            .comments
+            .iter()
+            .map(|c| c.to_dict(py))
+            .collect::<Result<_, _>>()?;
+        dict.set_item("comments", comment_dicts)?;
         dict.set_item("want_count", self.want_count)?;
         dict.set_item("watched_count", self.watched_count)?;
         dict.set_item("parse_success", self.parse_success)?;
+        let warning_dicts: Vec<_> = self
+            .warnings
+            .iter()
+            .map(|w| w.to_dict(py))
+            .collect::<Result<_, _>>()?;
+        dict.set_item("warnings", warning_dicts)?;
         Ok(dict)
     }
 
@@ -474,6 +811,17 @@ impl MovieDetail {
     }
 }
 
+impl MovieDetail {
+    fn compact_fields(&self) -> [(&'static str, &str); 4] {
+        [
+            ("c", &self.video_code),
+            ("t", &self.title),
+            ("r", &self.rate),
+            ("i", &self.poster_url),
+        ]
+    }
+}
+
 // ---------------------------------------------------------------------------
 // IndexPageResult
 // ---------------------------------------------------------------------------
@@ -487,6 +835,23 @@ pub struct IndexPageResult {
     pub movies: Vec<MovieIndexEntry>,
     #[pyo3(get, set)]
     pub page_title: String,
+    /// Literal href of the pagination "next" link; ``None`` on the last page.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub next_page_href: Option<String>,
+    /// Highest page number in the pagination nav; ``None`` without one or
+    /// when an ellipsis hides later pages.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub total_pages: Option<i32>,
+    /// Page marked current in the pagination nav, else the requested page.
+    #[serde(default = "default_current_page")]
+    #[pyo3(get, set)]
+    pub current_page: i32,
+}
+
+fn default_current_page() -> i32 {
+    1
 }
 
 impl Default for IndexPageResult {
@@ -495,6 +860,9 @@ impl Default for IndexPageResult {
             has_movie_list: false,
             movies: Vec::new(),
             page_title: String::new(),
+            next_page_href: None,
+            total_pages: None,
+            current_page: 1,
         }
     }
 }
@@ -502,15 +870,29 @@ impl Default for IndexPageResult {
 #[pymethods]
 impl IndexPageResult {
     #[new]
-    #[pyo3(signature = (has_movie_list=false, movies=vec![], page_title=String::new()))]
-    fn new(has_movie_list: bool, movies: Vec<MovieIndexEntry>, page_title: String) -> Self {
+    #[pyo3(signature = (has_movie_list=false, movies=vec![], page_title=String::new(), next_page_href=None, total_pages=None, current_page=1))]
+    fn new(
+        has_movie_list: bool,
+        movies: Vec<MovieIndexEntry>,
+        page_title: String,
+        next_page_href: Option<String>,
+        total_pages: Option<i32>,
+        current_page: i32,
+    ) -> Self {
         Self {
             has_movie_list,
             movies,
             page_title,
+            next_page_href,
+            total_pages,
+            current_page,
         }
     }
 
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("has_movie_list", self.has_movie_list)?;
@@ -521,6 +903,9 @@ impl IndexPageResult {
             .collect::<Result<_, _>>()?;
         dict.set_item("movies", movie_dicts)?;
         dict.set_item("page_title", &self.page_title)?;
+        dict.set_item("next_page_href", &self.next_page_href)?;
+        dict.set_item("total_pages", self.total_pages)?;
+        dict.set_item("current_page", self.current_page)?;
         Ok(dict)
     }
 }
@@ -542,18 +927,39 @@ pub struct CategoryPageResult {
     pub category_type: String,
     #[pyo3(get, set)]
     pub category_name: String,
+    /// Title count shown in the section header, when the page lists one.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub total_results: Option<i32>,
+    /// Episode count from the header of a series page; ``None`` elsewhere.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub series_total: Option<i32>,
+    /// Header blurb of a ``/video_codes/<prefix>`` page; ``None`` elsewhere.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub prefix_description: Option<String>,
+    /// Maker linked from a ``/video_codes/<prefix>`` header.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub prefix_maker: Option<MovieLink>,
 }
 
 #[pymethods]
 impl CategoryPageResult {
     #[new]
-    #[pyo3(signature = (has_movie_list=false, movies=vec![], page_title=String::new(), category_type=String::new(), category_name=String::new()))]
+    #[pyo3(signature = (has_movie_list=false, movies=vec![], page_title=String::new(), category_type=String::new(), category_name=String::new(), total_results=None, series_total=None, prefix_description=None, prefix_maker=None))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         has_movie_list: bool,
         movies: Vec<MovieIndexEntry>,
         page_title: String,
         category_type: String,
         category_name: String,
+        total_results: Option<i32>,
+        series_total: Option<i32>,
+        prefix_description: Option<String>,
+        prefix_maker: Option<MovieLink>,
     ) -> Self {
         Self {
             has_movie_list,
@@ -561,9 +967,17 @@ impl CategoryPageResult {
             page_title,
             category_type,
             category_name,
+            total_results,
+            series_total,
+            prefix_description,
+            prefix_maker,
         }
     }
 
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("has_movie_list", self.has_movie_list)?;
@@ -576,6 +990,11 @@ impl CategoryPageResult {
         dict.set_item("page_title", &self.page_title)?;
         dict.set_item("category_type", &self.category_type)?;
         dict.set_item("category_name", &self.category_name)?;
+        dict.set_item("total_results", self.total_results)?;
+        dict.set_item("series_total", self.series_total)?;
+        dict.set_item("prefix_description", &self.prefix_description)?;
+        let maker_dict = self.prefix_maker.as_ref().map(|m| m.to_dict(py)).transpose()?;
+        dict.set_item("prefix_maker", maker_dict)?;
         Ok(dict)
     }
 }
@@ -619,6 +1038,10 @@ impl TopPageResult {
         }
     }
 
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("has_movie_list", self.has_movie_list)?;
@@ -635,6 +1058,173 @@ impl TopPageResult {
     }
 }
 
+// ---------------------------------------------------------------------------
+// ActorIndexEntry / ActorPageResult (actor listing pages)
+// ---------------------------------------------------------------------------
+
+#[pyclass(name = "RustActorIndexEntry")]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ActorIndexEntry {
+    #[pyo3(get, set)]
+    pub name: String,
+    #[pyo3(get, set)]
+    pub href: String,
+    #[pyo3(get, set)]
+    pub avatar_url: Option<String>,
+    /// ``"female"`` / ``"male"`` from the ♀ / ♂ symbol, when shown.
+    #[pyo3(get, set)]
+    pub gender: Option<String>,
+    #[pyo3(get, set)]
+    pub page: i32,
+}
+
+#[pymethods]
+impl ActorIndexEntry {
+    #[new]
+    #[pyo3(signature = (name=String::new(), href=String::new(), avatar_url=None, gender=None, page=1))]
+    fn new(
+        name: String,
+        href: String,
+        avatar_url: Option<String>,
+        gender: Option<String>,
+        page: i32,
+    ) -> Self {
+        Self {
+            name,
+            href,
+            avatar_url,
+            gender,
+            page,
+        }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = new_dict(py);
+        dict.set_item("name", &self.name)?;
+        dict.set_item("href", &self.href)?;
+        dict.set_item("avatar_url", &self.avatar_url)?;
+        dict.set_item("gender", &self.gender)?;
+        dict.set_item("page", self.page)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RustActorIndexEntry(name='{}', href='{}')",
+            self.name, self.href
+        )
+    }
+}
+
+#[pyclass(name = "RustActorPageResult")]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ActorPageResult {
+    #[pyo3(get, set)]
+    pub has_actor_list: bool,
+    #[pyo3(get, set)]
+    pub actors: Vec<ActorIndexEntry>,
+    #[pyo3(get, set)]
+    pub page_title: String,
+}
+
+#[pymethods]
+impl ActorPageResult {
+    #[new]
+    #[pyo3(signature = (has_actor_list=false, actors=vec![], page_title=String::new()))]
+    fn new(has_actor_list: bool, actors: Vec<ActorIndexEntry>, page_title: String) -> Self {
+        Self {
+            has_actor_list,
+            actors,
+            page_title,
+        }
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = new_dict(py);
+        dict.set_item("has_actor_list", self.has_actor_list)?;
+        let actor_dicts: Vec<_> = self
+            .actors
+            .iter()
+            .map(|a| a.to_dict(py))
+            .collect::<Result<_, _>>()?;
+        dict.set_item("actors", actor_dicts)?;
+        dict.set_item("page_title", &self.page_title)?;
+        Ok(dict)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ActorDetail (``/actors/<id>`` page)
+// ---------------------------------------------------------------------------
+
+#[pyclass(name = "RustActorDetail")]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ActorDetail {
+    #[pyo3(get, set)]
+    pub name: String,
+    #[pyo3(get, set)]
+    pub aliases: Vec<String>,
+    #[pyo3(get, set)]
+    pub avatar_url: Option<String>,
+    /// Title count from the header (``371 部影片``), not ``movies.len()``.
+    #[pyo3(get, set)]
+    pub movie_count: Option<i32>,
+    #[pyo3(get, set)]
+    pub movies: Vec<MovieIndexEntry>,
+}
+
+#[pymethods]
+impl ActorDetail {
+    #[new]
+    #[pyo3(signature = (name=String::new(), aliases=vec![], avatar_url=None, movie_count=None, movies=vec![]))]
+    fn new(
+        name: String,
+        aliases: Vec<String>,
+        avatar_url: Option<String>,
+        movie_count: Option<i32>,
+        movies: Vec<MovieIndexEntry>,
+    ) -> Self {
+        Self {
+            name,
+            aliases,
+            avatar_url,
+            movie_count,
+            movies,
+        }
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = new_dict(py);
+        dict.set_item("name", &self.name)?;
+        dict.set_item("aliases", &self.aliases)?;
+        dict.set_item("avatar_url", &self.avatar_url)?;
+        dict.set_item("movie_count", self.movie_count)?;
+        let movie_dicts: Vec<_> = self
+            .movies
+            .iter()
+            .map(|m| m.to_dict(py))
+            .collect::<Result<_, _>>()?;
+        dict.set_item("movies", movie_dicts)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RustActorDetail(name='{}', movies={})",
+            self.name,
+            self.movies.len()
+        )
+    }
+}
+
 // ---------------------------------------------------------------------------
 // TagOption
 // ---------------------------------------------------------------------------
@@ -648,17 +1238,22 @@ pub struct TagOption {
     pub tag_id: String,
     #[pyo3(get, set)]
     pub selected: bool,
+    /// Matching-title count shown after the option name, e.g. ``巨乳 (1234)``.
+    #[serde(default)]
+    #[pyo3(get, set)]
+    pub count: Option<i32>,
 }
 
 #[pymethods]
 impl TagOption {
     #[new]
-    #[pyo3(signature = (name, tag_id=String::new(), selected=false))]
-    fn new(name: String, tag_id: String, selected: bool) -> Self {
+    #[pyo3(signature = (name, tag_id=String::new(), selected=false, count=None))]
+    fn new(name: String, tag_id: String, selected: bool, count: Option<i32>) -> Self {
         Self {
             name,
             tag_id,
             selected,
+            count,
         }
     }
 
@@ -667,6 +1262,7 @@ impl TagOption {
         dict.set_item("name", &self.name)?;
         dict.set_item("tag_id", &self.tag_id)?;
         dict.set_item("selected", self.selected)?;
+        dict.set_item("count", self.count)?;
         Ok(dict)
     }
 }
@@ -775,6 +1371,10 @@ impl TagPageResult {
         }
     }
 
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = new_dict(py);
         dict.set_item("has_movie_list", self.has_movie_list)?;
@@ -803,6 +1403,22 @@ impl TagPageResult {
         self.categories.iter().find(|c| c.name == name).cloned()
     }
 
+    /// Flat ``(category_id, tag_id)`` pairs from ``current_selections``,
+    /// expanding comma-joined values (``c1=5,7`` -> ``("1","5")``, ``("1","7")``).
+    pub fn get_selected_pairs(&self) -> Vec<(String, String)> {
+        let mut cids: Vec<&String> = self.current_selections.keys().collect();
+        cids.sort_by_key(|c| (c.parse::<u32>().unwrap_or(u32::MAX), c.as_str()));
+        cids.into_iter()
+            .flat_map(|cid| {
+                self.current_selections[cid]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(move |tid| (cid.clone(), tid.to_string()))
+            })
+            .collect()
+    }
+
     fn get_full_id_to_name_map(&self) -> HashMap<String, HashMap<String, String>> {
         self.categories
             .iter()
@@ -810,3 +1426,167 @@ impl TagPageResult {
             .collect()
     }
 }
+
+// ---------------------------------------------------------------------------
+// RunReport
+// ---------------------------------------------------------------------------
+
+/// Per-run scrape summary used for the notification email.
+#[pyclass(name = "RustRunReport")]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RunReport {
+    #[pyo3(get, set)]
+    pub total_fetched: u32,
+    #[pyo3(get, set)]
+    pub new_movies: u32,
+    #[pyo3(get, set)]
+    pub upgrades: u32,
+    #[pyo3(get, set)]
+    pub turnstile_hits: u32,
+    #[pyo3(get, set)]
+    pub proxy_bans: u32,
+    #[pyo3(get, set)]
+    pub failed_urls: Vec<String>,
+}
+
+#[pymethods]
+impl RunReport {
+    #[new]
+    #[pyo3(signature = (total_fetched=0, new_movies=0, upgrades=0, turnstile_hits=0, proxy_bans=0, failed_urls=vec![]))]
+    fn new(
+        total_fetched: u32,
+        new_movies: u32,
+        upgrades: u32,
+        turnstile_hits: u32,
+        proxy_bans: u32,
+        failed_urls: Vec<String>,
+    ) -> Self {
+        Self {
+            total_fetched,
+            new_movies,
+            upgrades,
+            turnstile_hits,
+            proxy_bans,
+            failed_urls,
+        }
+    }
+
+    fn add_failed_url(&mut self, url: String) {
+        if !self.failed_urls.contains(&url) {
+            self.failed_urls.push(url);
+        }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = new_dict(py);
+        dict.set_item("total_fetched", self.total_fetched)?;
+        dict.set_item("new_movies", self.new_movies)?;
+        dict.set_item("upgrades", self.upgrades)?;
+        dict.set_item("turnstile_hits", self.turnstile_hits)?;
+        dict.set_item("proxy_bans", self.proxy_bans)?;
+        dict.set_item("failed_urls", &self.failed_urls)?;
+        Ok(dict)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json_string(self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RustRunReport(total_fetched={}, new_movies={}, upgrades={}, failed={})",
+            self.total_fetched,
+            self.new_movies,
+            self.upgrades,
+            self.failed_urls.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_page_result_serde_defaults_match_default() {
+        let back: IndexPageResult =
+            serde_json::from_str(r#"{"has_movie_list": false, "movies": [], "page_title": ""}"#)
+                .unwrap();
+        assert_eq!(back.current_page, IndexPageResult::default().current_page);
+        assert_eq!(back.total_pages, None);
+    }
+
+    #[test]
+    fn test_movie_detail_json_round_trip() {
+        let detail = MovieDetail {
+            video_code: "ABC-123".into(),
+            rate_value: Some(4.2),
+            comments: vec![MovieComment {
+                author: "viewer".into(),
+                ..MovieComment::default()
+            }],
+            ..MovieDetail::default()
+        };
+        // ``to_json`` / ``from_json`` are thin ``serde_json`` wrappers; go
+        // through serde directly so the test does not need a Python error type.
+        let json = serde_json::to_string(&detail).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(value["maker"].is_null());
+        assert!(value["comments"][0]["score"].is_null());
+
+        let back: MovieDetail = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.video_code, "ABC-123");
+        assert_eq!(back.rate_value, Some(4.2));
+        assert_eq!(back.comments[0].author, "viewer");
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+    }
+
+    #[test]
+    fn test_compact_fields_use_short_keys_only() {
+        let entry = MovieIndexEntry::new(
+            "/v/abc".into(),
+            "ABC-123".into(),
+            "Title".into(),
+            "4.5".into(),
+            "10".into(),
+            "2024-01-01".into(),
+            vec!["tag".into()],
+            "https://c0.jdbstatic.com/covers/ab/abc.jpg".into(),
+            1,
+            None,
+            None,
+            Some(4.5),
+        );
+        let fields = entry.compact_fields();
+        let keys: Vec<&str> = fields.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec!["c", "t", "r", "i", "h"]);
+        assert_eq!(fields[0].1, "ABC-123");
+        assert_eq!(fields[4].1, "/v/abc");
+
+        let detail = MovieDetail {
+            video_code: "ABC-123".into(),
+            poster_url: "https://c0.jdbstatic.com/covers/ab/abc.jpg".into(),
+            ..MovieDetail::default()
+        };
+        let keys: Vec<&str> = detail.compact_fields().iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec!["c", "t", "r", "i"]);
+    }
+
+    #[test]
+    fn test_run_report_serializes() {
+        let mut report = RunReport::new(12, 3, 2, 1, 0, vec![]);
+        report.add_failed_url("https://javdb.com/v/abc".to_string());
+        report.add_failed_url("https://javdb.com/v/abc".to_string());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["total_fetched"], 12);
+        assert_eq!(json["new_movies"], 3);
+        assert_eq!(json["upgrades"], 2);
+        assert_eq!(json["turnstile_hits"], 1);
+        assert_eq!(json["proxy_bans"], 0);
+        assert_eq!(json["failed_urls"], serde_json::json!(["https://javdb.com/v/abc"]));
+
+        let back: RunReport = serde_json::from_value(json).unwrap();
+        assert_eq!(back.failed_urls.len(), 1);
+    }
+}
@@ -1,28 +1,97 @@
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone};
 use log::{debug, info};
 use parking_lot::Mutex;
+use pyo3::exceptions::PyIOError;
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 const TIME_FMT: &str = "%Y-%m-%d %H:%M:%S";
 
-/// Session-scoped ban record.  Bans are permanent for the lifetime of
-/// the current process — no duration / expiry tracking needed.
-#[derive(Clone, Debug)]
+/// Session-scoped ban record.  Without ``unban_time`` a ban is permanent
+/// for the lifetime of the current process.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProxyBanRecord {
     pub proxy_name: String,
     pub ban_time: DateTime<Local>,
     pub proxy_url: Option<String>,
+    #[serde(default)]
+    pub unban_time: Option<DateTime<Local>>,
+    /// Ban length the manager was configured with when this ban was made.
+    #[serde(default)]
+    pub ban_duration_days: Option<u32>,
+}
+
+/// One entry of a JSON ban snapshot: the full record plus its ban count.
+#[derive(Serialize, Deserialize)]
+struct JsonBanEntry {
+    #[serde(flatten)]
+    record: ProxyBanRecord,
+    #[serde(default = "default_ban_count")]
+    ban_count: u32,
+}
+
+/// Ban count of a proxy that has no active ban, written so the count
+/// outlives the ban.
+#[derive(Serialize, Deserialize)]
+struct JsonCountEntry {
+    proxy_name: String,
+    ban_count: u32,
+    is_banned: bool,
+}
+
+/// A JSON snapshot entry; entries without ``ban_time`` carry a count only.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonSnapshotEntry {
+    Ban(JsonBanEntry),
+    Count(JsonCountEntry),
+}
+
+fn default_ban_count() -> u32 {
+    1
+}
+
+impl ProxyBanRecord {
+    fn is_active(&self, now: DateTime<Local>) -> bool {
+        !matches!(self.unban_time, Some(until) if until <= now)
+    }
+
+    /// Combine an imported ban for the same proxy into this one.  The ban
+    /// with the later ``unban_time`` wins, a permanent one (``None``) above
+    /// any timed one; between two permanent bans the earlier one is kept.
+    fn merge_from(&mut self, incoming: ProxyBanRecord) {
+        let outlasts = match (self.unban_time, incoming.unban_time) {
+            (Some(current), Some(other)) => other > current,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => incoming.ban_time < self.ban_time,
+        };
+        if outlasts {
+            self.ban_time = incoming.ban_time;
+            self.unban_time = incoming.unban_time;
+        }
+        if self.proxy_url.is_none() {
+            self.proxy_url = incoming.proxy_url;
+        }
+    }
 }
 
 struct BanManagerInner {
     banned_proxies: Mutex<HashMap<String, ProxyBanRecord>>,
+    /// How many times each proxy has been banned.  Survives ``remove_ban``
+    /// and round-trips through ``export_bans`` / ``import_bans``, so a
+    /// snapshot carries the proxy's ban history across sessions.
+    ban_counts: Mutex<HashMap<String, u32>>,
+    /// Ban length for new bans; ``None`` bans until the process exits.
+    ban_duration_days: Mutex<Option<u32>>,
 }
 
 /// Session-scoped proxy ban manager.  Bans are kept in-memory only and
 /// are NOT persisted to disk.  Every new process starts with no bans.
-/// A ban is permanent for the lifetime of the process.
+/// A ban lasts ``ban_duration_days`` if set, otherwise for the lifetime
+/// of the process.
 #[pyclass(name = "RustProxyBanManager")]
 #[derive(Clone)]
 pub struct ProxyBanManager {
@@ -32,41 +101,46 @@ pub struct ProxyBanManager {
 #[pymethods]
 impl ProxyBanManager {
     #[new]
-    pub fn new() -> Self {
+    #[pyo3(signature = (ban_duration_days=None))]
+    pub fn new(ban_duration_days: Option<u32>) -> Self {
         info!("RustProxyBanManager initialised (session-scoped, in-memory only)");
         Self {
             inner: Arc::new(BanManagerInner {
                 banned_proxies: Mutex::new(HashMap::new()),
+                ban_counts: Mutex::new(HashMap::new()),
+                ban_duration_days: Mutex::new(ban_duration_days),
             }),
         }
     }
 
+    #[getter]
+    pub fn ban_duration_days(&self) -> Option<u32> {
+        *self.inner.ban_duration_days.lock()
+    }
+
+    /// Change the ban length for bans made from now on.  Existing bans keep
+    /// their ``unban_time``.
+    #[setter]
+    pub fn set_ban_duration_days(&self, ban_duration_days: Option<u32>) {
+        *self.inner.ban_duration_days.lock() = ban_duration_days;
+    }
+
+    /// Ban length in seconds, ``None`` when bans last until restart.
+    pub fn get_cooldown_seconds(&self) -> Option<i64> {
+        self.ban_duration_days().map(|d| i64::from(d) * 86_400)
+    }
+
     pub fn is_proxy_banned(&self, proxy_name: &str) -> bool {
-        let banned = self.inner.banned_proxies.lock();
+        let banned = self.active_bans();
         banned.contains_key(proxy_name)
     }
 
     #[pyo3(signature = (proxy_name, proxy_url=None))]
     pub fn add_ban(&self, proxy_name: &str, proxy_url: Option<String>) {
-        let mut banned = self.inner.banned_proxies.lock();
-        if banned.contains_key(proxy_name) {
-            debug!("Proxy '{}' is already banned this session, not updating", proxy_name);
-            return;
-        }
-
-        let record = ProxyBanRecord {
-            proxy_name: proxy_name.to_string(),
-            ban_time: Local::now(),
-            proxy_url,
-        };
-        banned.insert(proxy_name.to_string(), record);
-
-        debug!(
-            "Proxy '{}' banned [session-permanent]",
-            proxy_name
-        );
+        self.add_ban_for(proxy_name, proxy_url, self.ban_duration_days());
     }
 
+
     /// W6.A.2 follow-up — drop a ban record so the proxy can be
     /// returned to rotation by ``ProxyPool.unban_proxy``. Returns
     /// ``true`` iff an entry was present and removed.
@@ -86,7 +160,7 @@ impl ProxyBanManager {
 
     #[pyo3(signature = (include_ip=false))]
     pub fn get_ban_summary(&self, include_ip: bool) -> String {
-        let banned = self.inner.banned_proxies.lock();
+        let banned = self.active_bans();
 
         if banned.is_empty() {
             return "No proxies currently banned.".to_string();
@@ -111,7 +185,13 @@ impl ProxyBanManager {
                 "\n    Banned at: {}",
                 record.ban_time.format(TIME_FMT)
             ));
-            line.push_str("\n    Status: banned until process restart");
+            match record.unban_time {
+                Some(until) => line.push_str(&format!(
+                    "\n    Status: banned until {}",
+                    until.format(TIME_FMT)
+                )),
+                None => line.push_str("\n    Status: banned until process restart"),
+            }
             lines.push(line);
         }
 
@@ -119,12 +199,12 @@ impl ProxyBanManager {
     }
 
     pub fn get_banned_proxy_names(&self) -> Vec<String> {
-        let banned = self.inner.banned_proxies.lock();
+        let banned = self.active_bans();
         banned.keys().cloned().collect()
     }
 
     pub fn get_banned_proxies(&self) -> Vec<HashMap<String, String>> {
-        let banned = self.inner.banned_proxies.lock();
+        let banned = self.active_bans();
         banned
             .values()
             .map(|r| {
@@ -141,29 +221,609 @@ impl ProxyBanManager {
                 if let Some(ref url) = r.proxy_url {
                     m.insert("proxy_url".to_string(), url.clone());
                 }
+                if let Some(until) = r.unban_time {
+                    m.insert("unban_time".to_string(), until.format(TIME_FMT).to_string());
+                }
                 m
             })
             .collect()
     }
 
     pub fn get_banned_count(&self) -> usize {
-        self.inner.banned_proxies.lock().len()
+        self.active_bans().len()
+    }
+
+    /// Number of times ``proxy_name`` has been banned, including bans that
+    /// were later lifted and bans loaded from a snapshot.
+    pub fn get_ban_count(&self, proxy_name: &str) -> u32 {
+        self.inner
+            .ban_counts
+            .lock()
+            .get(proxy_name)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Proxies banned at least ``min_bans`` times, most-banned first (ties
+    /// by name).  Feeds the job that retires chronic offenders for good.
+    pub fn get_chronic_offenders(&self, min_bans: u32) -> Vec<String> {
+        let counts = self.inner.ban_counts.lock();
+        let mut offenders: Vec<(&String, u32)> = counts
+            .iter()
+            .filter(|(_, &n)| n >= min_bans)
+            .map(|(name, &n)| (name, n))
+            .collect();
+        offenders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        offenders.into_iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Write every current ban to ``path`` as one CSV snapshot, plus a
+    /// ``is_banned=false`` row with the ban count of every proxy banned
+    /// earlier but not now.  This is an explicit backup; the manager itself
+    /// still never persists on its own.  Returns the number of bans written.
+    pub fn export_bans(&self, path: &str) -> PyResult<usize> {
+        self.export_to(path).map_err(PyIOError::new_err)
+    }
+
+    /// Load a snapshot written by ``export_bans``.  With ``merge=false`` the
+    /// current bans are replaced; with ``merge=true`` they are combined, the
+    /// ban with the longer time left wins on conflict (as in
+    /// ``import_bans_json``) and the higher ban count is kept.  Each ban
+    /// keeps the ``unban_time`` it was written with, so a reload does not
+    /// extend or shorten it.  A manager without a ``ban_duration_days`` of
+    /// its own takes the one recorded with the latest ban.
+    #[pyo3(signature = (path, merge=true))]
+    pub fn import_bans(&self, path: &str, merge: bool) -> PyResult<usize> {
+        self.import_from(path, merge).map_err(PyIOError::new_err)
+    }
+
+    /// Write every current ban to ``path`` as JSON, for sharing the ban
+    /// list between machines.  Times carry their UTC offset.  Ban counts of
+    /// proxies without an active ban are written as in ``export_bans``.
+    pub fn export_bans_json(&self, path: &str) -> PyResult<usize> {
+        self.export_json_to(path).map_err(PyIOError::new_err)
+    }
+
+    /// Merge a snapshot written by ``export_bans_json`` into the current
+    /// bans.  Expired records are skipped; on conflict the later
+    /// ``unban_time`` wins (a permanent ban beats any timed one).  The ban
+    /// duration is restored as in ``import_bans``.
+    pub fn import_bans_json(&self, path: &str) -> PyResult<usize> {
+        self.import_json_from(path).map_err(PyIOError::new_err)
+    }
+}
+
+const SNAPSHOT_HEADER: &[&str] = &[
+    "proxy_name",
+    "ban_time",
+    "proxy_url",
+    "ban_count",
+    "unban_time",
+    "ban_duration_days",
+    "is_banned",
+];
+
+impl ProxyBanManager {
+    /// ``add_ban`` with an explicit ban length, so pools sharing the global
+    /// manager each ban for their own ``ban_duration_days``.
+    pub fn add_ban_for(
+        &self,
+        proxy_name: &str,
+        proxy_url: Option<String>,
+        ban_duration_days: Option<u32>,
+    ) {
+        let mut banned = self.active_bans();
+        if banned.contains_key(proxy_name) {
+            debug!("Proxy '{}' is already banned this session, not updating", proxy_name);
+            return;
+        }
+
+        let now = Local::now();
+        let record = ProxyBanRecord {
+            proxy_name: proxy_name.to_string(),
+            ban_time: now,
+            proxy_url,
+            unban_time: ban_duration_days.map(|d| now + Duration::days(i64::from(d))),
+            ban_duration_days,
+        };
+        banned.insert(proxy_name.to_string(), record);
+        *self
+            .inner
+            .ban_counts
+            .lock()
+            .entry(proxy_name.to_string())
+            .or_insert(0) += 1;
+
+        match ban_duration_days {
+            Some(days) => debug!("Proxy '{}' banned for {} days", proxy_name, days),
+            None => debug!("Proxy '{}' banned [session-permanent]", proxy_name),
+        }
+    }
+
+    /// Lock the ban table with expired timed bans dropped.
+    fn active_bans(&self) -> parking_lot::MutexGuard<'_, HashMap<String, ProxyBanRecord>> {
+        let mut banned = self.inner.banned_proxies.lock();
+        let now = Local::now();
+        banned.retain(|name, record| {
+            let active = record.is_active(now);
+            if !active {
+                debug!("Proxy '{}' ban expired", name);
+            }
+            active
+        });
+        banned
+    }
+
+    /// Adopt the ban duration of the most recent imported ban if this
+    /// manager was created without one.
+    fn restore_ban_duration<'a>(&self, records: impl Iterator<Item = &'a ProxyBanRecord>) {
+        let mut duration = self.inner.ban_duration_days.lock();
+        if duration.is_some() {
+            return;
+        }
+        if let Some(latest) = records
+            .filter(|r| r.ban_duration_days.is_some())
+            .max_by_key(|r| r.ban_time)
+        {
+            *duration = latest.ban_duration_days;
+            info!("Restored ban duration of {:?} days from snapshot", *duration);
+        }
+    }
+
+    fn export_to(&self, path: &str) -> Result<usize, String> {
+        let mut records: Vec<ProxyBanRecord> = self.active_bans().values().cloned().collect();
+        records.sort_by_key(|r| r.ban_time);
+        let counts = self.inner.ban_counts.lock().clone();
+
+        let mut writer = csv::Writer::from_path(path).map_err(|e| e.to_string())?;
+        writer
+            .write_record(SNAPSHOT_HEADER)
+            .map_err(|e| e.to_string())?;
+        for r in &records {
+            let ban_time = r.ban_time.format(TIME_FMT).to_string();
+            let ban_count = counts.get(&r.proxy_name).copied().unwrap_or(1).to_string();
+            let unban_time = r
+                .unban_time
+                .map(|t| t.format(TIME_FMT).to_string())
+                .unwrap_or_default();
+            let ban_duration_days =
+                r.ban_duration_days.map(|d| d.to_string()).unwrap_or_default();
+            writer
+                .write_record([
+                    r.proxy_name.as_str(),
+                    ban_time.as_str(),
+                    r.proxy_url.as_deref().unwrap_or(""),
+                    ban_count.as_str(),
+                    unban_time.as_str(),
+                    ban_duration_days.as_str(),
+                    "true",
+                ])
+                .map_err(|e| e.to_string())?;
+        }
+        for (name, ban_count) in lifted_counts(&records, &counts) {
+            let ban_count = ban_count.to_string();
+            writer
+                .write_record([name.as_str(), "", "", ban_count.as_str(), "", "", "false"])
+                .map_err(|e| e.to_string())?;
+        }
+        writer.flush().map_err(|e| e.to_string())?;
+        info!("Exported {} proxy bans to snapshot", records.len());
+        Ok(records.len())
+    }
+
+    fn import_from(&self, path: &str, merge: bool) -> Result<usize, String> {
+        let mut reader = csv::Reader::from_path(path).map_err(|e| e.to_string())?;
+        let mut loaded = Vec::new();
+        let mut lifted = Vec::new();
+        for row in reader.records() {
+            let row = row.map_err(|e| e.to_string())?;
+            let proxy_name = row.get(0).unwrap_or("").to_string();
+            if proxy_name.is_empty() {
+                continue;
+            }
+            // Older snapshots lack ban_count, unban_time, ban_duration_days
+            // and is_banned.
+            let ban_count = row.get(3).and_then(|c| c.parse().ok()).unwrap_or(1);
+            if row.get(6) == Some("false") {
+                lifted.push((proxy_name, ban_count));
+                continue;
+            }
+            let parse_time = |t: &str| {
+                NaiveDateTime::parse_from_str(t, TIME_FMT)
+                    .ok()
+                    .and_then(|t| Local.from_local_datetime(&t).earliest())
+            };
+            let ban_time = row
+                .get(1)
+                .and_then(parse_time)
+                .ok_or_else(|| format!("invalid ban_time for '{}'", proxy_name))?;
+            let proxy_url = row.get(2).filter(|u| !u.is_empty()).map(str::to_string);
+            let unban_time = row.get(4).and_then(parse_time);
+            let ban_duration_days = row.get(5).and_then(|d| d.parse().ok());
+            loaded.push((
+                ProxyBanRecord {
+                    proxy_name,
+                    ban_time,
+                    proxy_url,
+                    unban_time,
+                    ban_duration_days,
+                },
+                ban_count,
+            ));
+        }
+
+        let count = loaded.len();
+        self.restore_ban_duration(loaded.iter().map(|(record, _)| record));
+        let mut banned = self.inner.banned_proxies.lock();
+        let mut counts = self.inner.ban_counts.lock();
+        if !merge {
+            banned.clear();
+            counts.clear();
+        }
+        for (name, ban_count) in lifted {
+            let total = counts.entry(name).or_insert(0);
+            *total = (*total).max(ban_count);
+        }
+        for (record, ban_count) in loaded {
+            let total = counts.entry(record.proxy_name.clone()).or_insert(0);
+            *total = (*total).max(ban_count);
+            match banned.get_mut(&record.proxy_name) {
+                Some(existing) => existing.merge_from(record),
+                None => {
+                    banned.insert(record.proxy_name.clone(), record);
+                }
+            }
+        }
+        info!(
+            "Imported {} proxy bans from snapshot ({})",
+            count,
+            if merge { "merged" } else { "replaced" }
+        );
+        Ok(count)
+    }
+
+    fn export_json_to(&self, path: &str) -> Result<usize, String> {
+        let mut records: Vec<ProxyBanRecord> = self.active_bans().values().cloned().collect();
+        records.sort_by_key(|r| r.ban_time);
+        let counts = self.inner.ban_counts.lock().clone();
+        let lifted = lifted_counts(&records, &counts);
+        let written = records.len();
+        let entries: Vec<JsonSnapshotEntry> = records
+            .into_iter()
+            .map(|record| {
+                JsonSnapshotEntry::Ban(JsonBanEntry {
+                    ban_count: counts.get(&record.proxy_name).copied().unwrap_or(1),
+                    record,
+                })
+            })
+            .chain(lifted.into_iter().map(|(proxy_name, ban_count)| {
+                JsonSnapshotEntry::Count(JsonCountEntry {
+                    proxy_name,
+                    ban_count,
+                    is_banned: false,
+                })
+            }))
+            .collect();
+
+        let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &entries)
+            .map_err(|e| e.to_string())?;
+        info!("Exported {} proxy bans to JSON snapshot", written);
+        Ok(written)
+    }
+
+    fn import_json_from(&self, path: &str) -> Result<usize, String> {
+        let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+        let entries: Vec<JsonSnapshotEntry> =
+            serde_json::from_reader(std::io::BufReader::new(file)).map_err(|e| e.to_string())?;
+
+        let now = Local::now();
+        self.restore_ban_duration(entries.iter().filter_map(|e| match e {
+            JsonSnapshotEntry::Ban(entry) => Some(&entry.record),
+            JsonSnapshotEntry::Count(_) => None,
+        }));
+        let mut banned = self.active_bans();
+        let mut counts = self.inner.ban_counts.lock();
+        let mut imported = 0;
+        for entry in entries {
+            let (record, ban_count) = match entry {
+                JsonSnapshotEntry::Ban(JsonBanEntry { record, ban_count }) => (record, ban_count),
+                JsonSnapshotEntry::Count(JsonCountEntry { proxy_name, ban_count, .. }) => {
+                    if !proxy_name.is_empty() {
+                        let total = counts.entry(proxy_name).or_insert(0);
+                        *total = (*total).max(ban_count);
+                    }
+                    continue;
+                }
+            };
+            if record.proxy_name.is_empty() {
+                continue;
+            }
+            // An expired ban still carries the proxy's ban count.
+            let total = counts.entry(record.proxy_name.clone()).or_insert(0);
+            *total = (*total).max(ban_count);
+            if !record.is_active(now) {
+                continue;
+            }
+            imported += 1;
+            match banned.get_mut(&record.proxy_name) {
+                Some(existing) => existing.merge_from(record),
+                None => {
+                    banned.insert(record.proxy_name.clone(), record);
+                }
+            }
+        }
+        info!("Imported {} proxy bans from JSON snapshot", imported);
+        Ok(imported)
     }
 }
 
+/// ``(name, count)`` of every proxy with a ban count but no active ban,
+/// sorted by name.
+fn lifted_counts(active: &[ProxyBanRecord], counts: &HashMap<String, u32>) -> Vec<(String, u32)> {
+    let mut lifted: Vec<(String, u32)> = counts
+        .iter()
+        .filter(|(name, _)| !active.iter().any(|r| &r.proxy_name == *name))
+        .map(|(name, &count)| (name.clone(), count))
+        .collect();
+    lifted.sort();
+    lifted
+}
+
 use once_cell::sync::OnceCell;
 
 static GLOBAL_BAN_MANAGER: OnceCell<ProxyBanManager> = OnceCell::new();
 
-pub fn get_ban_manager(_ban_log_file: &str) -> ProxyBanManager {
-    GLOBAL_BAN_MANAGER
-        .get_or_init(ProxyBanManager::new)
-        .clone()
+/// The process-wide ban manager.  A ``Some`` duration becomes its default
+/// ban length (also when it already exists, so the last caller wins);
+/// ``None`` leaves the current one alone.  Proxy pools do not set it: each
+/// bans with its own duration through ``add_ban_for``.
+pub fn get_ban_manager(ban_duration_days: Option<u32>) -> ProxyBanManager {
+    let manager = GLOBAL_BAN_MANAGER
+        .get_or_init(|| ProxyBanManager::new(ban_duration_days))
+        .clone();
+    if ban_duration_days.is_some() && manager.ban_duration_days() != ban_duration_days {
+        manager.set_ban_duration_days(ban_duration_days);
+    }
+    manager
 }
 
 #[pyfunction]
-pub fn get_global_ban_manager() -> ProxyBanManager {
-    GLOBAL_BAN_MANAGER
-        .get_or_init(ProxyBanManager::new)
-        .clone()
+#[pyo3(signature = (ban_duration_days=None))]
+pub fn get_global_ban_manager(ban_duration_days: Option<u32>) -> ProxyBanManager {
+    get_ban_manager(ban_duration_days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("ban_snapshot_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let source = ProxyBanManager::new(None);
+        source.add_ban("proxy-a", Some("http://10.0.0.1:8080".to_string()));
+        source.add_ban("proxy-b", None);
+        assert_eq!(source.export_to(path).unwrap(), 2);
+
+        let restored = ProxyBanManager::new(None);
+        restored.add_ban("proxy-c", None);
+        assert_eq!(restored.import_from(path, false).unwrap(), 2);
+        let mut names = restored.get_banned_proxy_names();
+        names.sort();
+        assert_eq!(names, vec!["proxy-a", "proxy-b"]);
+        let a = restored.inner.banned_proxies.lock()["proxy-a"].clone();
+        assert_eq!(a.proxy_url.as_deref(), Some("http://10.0.0.1:8080"));
+
+        let merged = ProxyBanManager::new(None);
+        merged.add_ban("proxy-a", None);
+        merged.add_ban("proxy-c", None);
+        merged.import_from(path, true).unwrap();
+        assert_eq!(merged.get_banned_count(), 3);
+        let a = merged.inner.banned_proxies.lock()["proxy-a"].clone();
+        assert_eq!(a.proxy_url.as_deref(), Some("http://10.0.0.1:8080"));
+        assert!(a.ban_time <= Local::now());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_csv_merge_keeps_longer_ban() {
+        let path = std::env::temp_dir().join(format!("ban_longer_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        // The snapshot holds a 7-day ban for proxy-a and a 1-day ban for proxy-b.
+        let source = ProxyBanManager::new(Some(7));
+        source.add_ban("proxy-a", None);
+        source.add_ban("proxy-b", None);
+        source.inner.banned_proxies.lock().get_mut("proxy-b").unwrap().unban_time =
+            Some(Local::now() + Duration::days(1));
+        source.export_to(path).unwrap();
+        let snapshot_a = source.inner.banned_proxies.lock()["proxy-a"].unban_time;
+
+        // Locally proxy-a has 1 day left and proxy-b 3 days.
+        let local = ProxyBanManager::new(Some(1));
+        local.add_ban("proxy-a", None);
+        local.add_ban("proxy-b", None);
+        let local_b = Some(Local::now() + Duration::days(3));
+        local.inner.banned_proxies.lock().get_mut("proxy-b").unwrap().unban_time = local_b;
+        local.import_from(path, true).unwrap();
+
+        let banned = local.inner.banned_proxies.lock();
+        let drift = banned["proxy-a"].unban_time.unwrap() - snapshot_a.unwrap();
+        assert!(drift.num_seconds().abs() <= 1);
+        assert_eq!(banned["proxy-b"].unban_time, local_b);
+        drop(banned);
+
+        // A permanent local ban outlasts any timed one.
+        let permanent = ProxyBanManager::new(None);
+        permanent.add_ban("proxy-a", None);
+        permanent.import_from(path, true).unwrap();
+        assert!(permanent.inner.banned_proxies.lock()["proxy-a"].unban_time.is_none());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_ban_duration_sets_unban_time() {
+        let path =
+            std::env::temp_dir().join(format!("ban_duration_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let manager = ProxyBanManager::new(Some(1));
+        assert_eq!(manager.get_cooldown_seconds(), Some(86_400));
+        manager.add_ban("proxy-a", None);
+        let record = manager.inner.banned_proxies.lock()["proxy-a"].clone();
+        let ban_for = record.unban_time.unwrap() - record.ban_time;
+        assert_eq!(ban_for, Duration::hours(24));
+        assert!(manager.is_proxy_banned("proxy-a"));
+
+        // The unban time survives a reload into a manager with another duration.
+        manager.export_to(path).unwrap();
+        let restored = ProxyBanManager::new(None);
+        restored.import_from(path, false).unwrap();
+        let reloaded = restored.inner.banned_proxies.lock()["proxy-a"].clone();
+        let drift = reloaded.unban_time.unwrap() - record.unban_time.unwrap();
+        assert!(drift.num_seconds().abs() <= 1);
+        assert_eq!(restored.ban_duration_days(), Some(1));
+
+        restored.inner.banned_proxies.lock().get_mut("proxy-a").unwrap().unban_time =
+            Some(Local::now() - Duration::seconds(1));
+        assert!(!restored.is_proxy_banned("proxy-a"));
+        assert_eq!(restored.get_banned_count(), 0);
+
+        let permanent = ProxyBanManager::new(None);
+        permanent.add_ban("proxy-b", None);
+        assert_eq!(permanent.get_cooldown_seconds(), None);
+        assert!(permanent.inner.banned_proxies.lock()["proxy-b"].unban_time.is_none());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_global_manager_uses_configured_duration() {
+        let manager = get_ban_manager(Some(2));
+        assert_eq!(manager.get_cooldown_seconds(), Some(2 * 86_400));
+        manager.add_ban("proxy-configured", None);
+        let record = manager.inner.banned_proxies.lock()["proxy-configured"].clone();
+        assert_eq!(record.unban_time.unwrap() - record.ban_time, Duration::days(2));
+        assert_eq!(record.ban_duration_days, Some(2));
+
+        // Asking again without a duration keeps the configured one.
+        assert_eq!(get_ban_manager(None).ban_duration_days(), Some(2));
+        manager.remove_ban("proxy-configured");
+    }
+
+    #[test]
+    fn test_pools_ban_for_their_own_duration() {
+        // Two pools on the shared manager, configured with 1 and 5 days.
+        let manager = get_ban_manager(None);
+        manager.add_ban_for("pool-short", None, Some(1));
+        manager.add_ban_for("pool-long", None, Some(5));
+
+        for (name, days) in [("pool-short", 1), ("pool-long", 5)] {
+            let record = manager.inner.banned_proxies.lock()[name].clone();
+            assert_eq!(record.ban_duration_days, Some(days));
+            assert_eq!(record.unban_time.unwrap() - record.ban_time, Duration::days(days.into()));
+            manager.remove_ban(name);
+        }
+    }
+
+    #[test]
+    fn test_json_export_import_round_trip() {
+        let path = std::env::temp_dir().join(format!("ban_json_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let source = ProxyBanManager::new(Some(2));
+        source.add_ban("proxy-a", Some("http://1.2.3.4:8080".to_string()));
+        source.add_ban("proxy-b", None);
+        source.inner.banned_proxies.lock().insert(
+            "proxy-old".to_string(),
+            ProxyBanRecord {
+                proxy_name: "proxy-old".to_string(),
+                ban_time: Local::now() - Duration::days(3),
+                proxy_url: None,
+                unban_time: Some(Local::now() + Duration::days(1)),
+                ban_duration_days: Some(2),
+            },
+        );
+        assert_eq!(source.export_json_to(path).unwrap(), 3);
+        // Expire one record after export: import must skip it.
+        let text = std::fs::read_to_string(path).unwrap();
+        let mut entries: Vec<JsonBanEntry> = serde_json::from_str(&text).unwrap();
+        for entry in entries.iter_mut().filter(|e| e.record.proxy_name == "proxy-old") {
+            entry.record.unban_time = Some(Local::now() - Duration::days(1));
+        }
+        std::fs::write(path, serde_json::to_string(&entries).unwrap()).unwrap();
+
+        let target = ProxyBanManager::new(None);
+        assert_eq!(target.import_json_from(path).unwrap(), 2);
+        assert_eq!(target.ban_duration_days(), Some(2));
+        let mut names = target.get_banned_proxy_names();
+        names.sort();
+        assert_eq!(names, ["proxy-a", "proxy-b"]);
+        let a = target.inner.banned_proxies.lock()["proxy-a"].clone();
+        assert_eq!(a.proxy_url.as_deref(), Some("http://1.2.3.4:8080"));
+        assert_eq!(a.unban_time, source.inner.banned_proxies.lock()["proxy-a"].unban_time);
+
+        // A permanent local ban is not shortened by a timed one on merge.
+        let local = ProxyBanManager::new(None);
+        local.add_ban("proxy-a", None);
+        local.import_json_from(path).unwrap();
+        assert!(local.inner.banned_proxies.lock()["proxy-a"].unban_time.is_none());
+        assert_eq!(
+            local.inner.banned_proxies.lock()["proxy-a"].proxy_url.as_deref(),
+            Some("http://1.2.3.4:8080")
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_chronic_offenders_by_ban_count() {
+        let path =
+            std::env::temp_dir().join(format!("ban_counts_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let manager = ProxyBanManager::new(None);
+        for _ in 0..3 {
+            manager.add_ban("proxy-a", None);
+            manager.remove_ban("proxy-a");
+        }
+        manager.add_ban("proxy-b", None);
+        manager.add_ban("proxy-b", None); // still banned: not a new ban
+        manager.remove_ban("proxy-b");
+        manager.add_ban("proxy-b", None);
+        manager.add_ban("proxy-c", None);
+        assert_eq!(manager.get_ban_count("proxy-a"), 3);
+        assert_eq!(manager.get_ban_count("proxy-b"), 2);
+        assert_eq!(manager.get_ban_count("proxy-z"), 0);
+        assert_eq!(manager.get_chronic_offenders(2), vec!["proxy-a", "proxy-b"]);
+        assert_eq!(manager.get_chronic_offenders(4), Vec::<String>::new());
+
+        // proxy-a is not banned now: its count is still written, but no ban.
+        assert_eq!(manager.export_to(path).unwrap(), 2);
+        let restored = ProxyBanManager::new(None);
+        assert_eq!(restored.import_from(path, false).unwrap(), 2);
+        assert_eq!(restored.get_ban_count("proxy-a"), 3);
+        assert!(!restored.is_proxy_banned("proxy-a"));
+        assert_eq!(
+            restored.get_chronic_offenders(1),
+            vec!["proxy-a", "proxy-b", "proxy-c"]
+        );
+
+        let json_path = format!("{}.json", path);
+        assert_eq!(manager.export_json_to(&json_path).unwrap(), 2);
+        let restored = ProxyBanManager::new(None);
+        assert_eq!(restored.import_json_from(&json_path).unwrap(), 2);
+        assert_eq!(restored.get_ban_count("proxy-a"), 3);
+        assert!(!restored.is_proxy_banned("proxy-a"));
+        assert_eq!(restored.get_chronic_offenders(2), vec!["proxy-a", "proxy-b"]);
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(json_path).unwrap();
+    }
 }
@@ -1,3 +1,4 @@
 pub mod ban_manager;
+pub mod health;
 pub mod masking;
 pub mod pool;
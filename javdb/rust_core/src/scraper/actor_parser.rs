@@ -0,0 +1,109 @@
+use log::debug;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{Html, Selector};
+
+use crate::models::ActorDetail;
+use crate::scraper::common::{get_text_content, unwrap_saved_page};
+use crate::scraper::index_parser::{extract_total_results, is_total_results_text, parse_index_page};
+
+static SEL_ACTOR_NAME: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("span.actor-section-name").unwrap());
+static SEL_SECTION_META: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("span.section-meta").unwrap());
+static SEL_AVATAR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(".actor-avatar .avatar, .actor-avatar img").unwrap());
+
+static BG_URL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"url\(\s*['"]?([^'")]+)['"]?\s*\)"#).unwrap());
+
+/// Split an alias line (``A, B`` / ``A、B``) into trimmed names.
+fn split_names(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split([',', '，', '、'])
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Avatar from the header: a ``background-image`` on ``span.avatar`` or an
+/// ``<img>`` inside ``.actor-avatar``.
+fn extract_avatar(document: &Html) -> Option<String> {
+    document.select(&SEL_AVATAR).find_map(|el| {
+        let value = el.value();
+        let from_style = value
+            .attr("style")
+            .and_then(|style| BG_URL_RE.captures(style))
+            .map(|c| c[1].trim().to_string());
+        from_style
+            .or_else(|| value.attr("src").map(str::to_string))
+            .or_else(|| value.attr("data-src").map(str::to_string))
+            .filter(|url| !url.is_empty())
+    })
+}
+
+/// Parse an ``/actors/<id>`` page: the header block (name, aliases, avatar,
+/// title count) and the movie list below it.  Missing header parts are left
+/// empty.
+pub fn parse_actor_detail(html_content: &str) -> ActorDetail {
+    let unwrapped = unwrap_saved_page(html_content);
+    let document = Html::parse_document(&unwrapped);
+
+    // The name span sometimes lists aliases after the primary name.
+    let mut names: Vec<String> = document
+        .select(&SEL_ACTOR_NAME)
+        .next()
+        .map(|span| split_names(&get_text_content(&span)).collect())
+        .unwrap_or_default();
+    for meta in document.select(&SEL_SECTION_META) {
+        let text = get_text_content(&meta);
+        if !is_total_results_text(&text) {
+            names.extend(split_names(&text));
+        }
+    }
+
+    let mut detail = ActorDetail::default();
+    let mut names = names.into_iter();
+    detail.name = names.next().unwrap_or_default();
+    for alias in names {
+        if alias != detail.name && !detail.aliases.contains(&alias) {
+            detail.aliases.push(alias);
+        }
+    }
+    detail.avatar_url = extract_avatar(&document);
+    detail.movie_count = extract_total_results(&document);
+    detail.movies = parse_index_page(&unwrapped, 1).movies;
+
+    debug!(
+        "Parsed actor: name={}, aliases={}, movies={}",
+        detail.name,
+        detail.aliases.len(),
+        detail.movies.len()
+    );
+    detail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_actor_detail() {
+        let html = include_str!("../../tests/fixtures/parser/actor_detail.html");
+        let detail = parse_actor_detail(html);
+        assert_eq!(detail.name, "Actress One");
+        assert_eq!(detail.aliases, ["Alias A", "Alias B", "Alias C"]);
+        assert_eq!(
+            detail.avatar_url.as_deref(),
+            Some("https://c0.jdbstatic.com/avatars/ab/AbC1.jpg")
+        );
+        assert_eq!(detail.movie_count, Some(371));
+        let codes: Vec<&str> = detail.movies.iter().map(|m| m.video_code.as_str()).collect();
+        assert_eq!(codes, ["ACT-001", "ACT-002"]);
+
+        let empty = parse_actor_detail("<html><body></body></html>");
+        assert_eq!(empty.name, "");
+        assert!(empty.aliases.is_empty());
+        assert_eq!(empty.avatar_url, None);
+        assert_eq!(empty.movie_count, None);
+        assert!(empty.movies.is_empty());
+    }
+}
@@ -1,3 +1,7 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Duration, Local};
+use std::borrow::Cow;
 use log::debug;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -10,7 +14,18 @@ use crate::models::MovieLink;
 static RATE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+\.?\d*)分").unwrap());
 static RATE_RE_EN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+\.?\d*),\s*by").unwrap());
 static COMMENT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"由(\d+)人評價").unwrap());
-static COMMENT_RE_EN: Lazy<Regex> = Lazy::new(|| Regex::new(r"by\s+(\d+)\s+users?").unwrap());
+static COMMENT_RE_EN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"by\s+(\d+)\s+(?:users?|reviews?|ratings?)").unwrap());
+// Number-only score ("4.47" or "4.47, ...") with no locale suffix at all.
+static RATE_RE_BARE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(\d+(?:\.\d+)?)\s*(?:,|$)").unwrap());
+
+static DAYS_AGO_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(\d+)\s*(?:天前|日前|days?\s+ago)$").unwrap());
+static HOURS_AGO_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(\d+)\s*(?:小時前|小时前|hours?\s+ago)$").unwrap());
+static MINUTES_AGO_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(\d+)\s*(?:分鐘前|分钟前|(?:minutes?|mins?)\s+ago)$").unwrap());
 
 static PAGE_TYPE_PATTERNS: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
     vec![
@@ -27,6 +42,16 @@ static PAGE_TYPE_PATTERNS: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
     ]
 });
 
+const REMOVED_TITLE_PATTERNS: &[&str] = &[
+    "頁面不存在",
+    "页面不存在",
+    "影片不存在",
+    "page not found",
+    "the page you requested does not exist",
+];
+
+const NO_CONTENT_PATTERNS: &[&str] = &["No content yet", "No result", "暫無內容", "暂无内容"];
+
 static URL_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r#"(?:href|url)=["']?(?:\(\d+\))?(https?://[^"'>\s)]+)"#).unwrap()
 });
@@ -35,6 +60,7 @@ pub fn extract_rate_and_comments(score_text: &str) -> (String, String) {
     let rate = RATE_RE
         .captures(score_text)
         .or_else(|| RATE_RE_EN.captures(score_text))
+        .or_else(|| RATE_RE_BARE.captures(score_text))
         .and_then(|c| c.get(1))
         .map_or(String::new(), |m| m.as_str().to_string());
 
@@ -47,13 +73,56 @@ pub fn extract_rate_and_comments(score_text: &str) -> (String, String) {
     (rate, comment_count)
 }
 
+/// Numeric form of a scraped ``rate`` string, kept only within JavDB's
+/// 0–5 scale so a mis-parsed number never reaches sorting or filtering.
+pub fn parse_rate_value(rate: &str) -> Option<f64> {
+    let value: f64 = rate.trim().parse().ok()?;
+    (0.0..=5.0).contains(&value).then_some(value)
+}
+
+/// Resolve a relative date ("今天", "yesterday", "3天前", "2 hours ago") to
+/// ``YYYY-MM-DD`` against ``reference`` (defaults to now).
+///
+/// Returns ``None`` when ``text`` is not a relative expression, so callers
+/// keep absolute dates as scraped.
+pub fn resolve_relative_date(text: &str, reference: Option<DateTime<Local>>) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let reference = reference.unwrap_or_else(Local::now);
+    let lower = text.to_lowercase();
+
+    let resolved = match lower.as_str() {
+        "今天" | "今日" | "today" => reference,
+        "昨天" | "昨日" | "yesterday" => reference - Duration::days(1),
+        "前天" => reference - Duration::days(2),
+        _ => {
+            if let Some(caps) = DAYS_AGO_RE.captures(&lower) {
+                reference - Duration::days(caps[1].parse::<i64>().ok()?)
+            } else if let Some(caps) = HOURS_AGO_RE.captures(&lower) {
+                reference - Duration::hours(caps[1].parse::<i64>().ok()?)
+            } else if let Some(caps) = MINUTES_AGO_RE.captures(&lower) {
+                reference - Duration::minutes(caps[1].parse::<i64>().ok()?)
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(resolved.format("%Y-%m-%d").to_string())
+}
+
 pub fn extract_movie_link(a_tag: &ElementRef) -> Option<MovieLink> {
     let name = get_text_content(a_tag).trim().to_string();
     if name.is_empty() {
         return None;
     }
     let href = a_tag.value().attr("href").unwrap_or("").to_string();
-    Some(MovieLink { name, href })
+    Some(MovieLink {
+        name,
+        href,
+        work_count: None,
+    })
 }
 
 pub fn extract_all_movie_links(parent: &ElementRef) -> Vec<MovieLink> {
@@ -95,6 +164,49 @@ pub fn normalize_javdb_href_path(href: &str) -> String {
     }
 }
 
+/// Resolve a scraped ``href`` against ``base`` with URL join semantics.
+///
+/// Absolute, protocol-relative (``//host/x``), root-relative and relative
+/// hrefs all resolve the way a browser would.  An unparseable ``base`` or
+/// ``href`` yields the trimmed ``href`` unchanged.
+pub fn resolve_url(base: &str, href: &str) -> String {
+    let h = href.trim();
+    Url::parse(base)
+        .and_then(|b| b.join(h))
+        .map(|u| u.to_string())
+        .unwrap_or_else(|_| h.to_string())
+}
+
+/// ``PREFIX-NNN`` at the end of a code: a ``-`` separated prefix whose last
+/// segment starts with a letter (``FC2-PPV``, ``T28``), or a letters-only
+/// prefix with no separator (``ABC00123``).
+static VIDEO_CODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:((?:[A-Z0-9]+-)*[A-Z][A-Z0-9]*)-|([A-Z]+))0*(\d+)([A-Z]*)$").unwrap()
+});
+
+/// Canonical form of a video code for comparison: upper-cased, with the
+/// prefix and number joined by one ``-`` and leading zeros dropped, so
+/// ``abc00123`` and ``ABC-123`` both become ``ABC-123``.  Codes that do not
+/// end in ``PREFIX-NNN`` (date-style ``CARIB-010120-001``,
+/// ``1PON-010120_001``) are only trimmed and upper-cased.
+pub fn normalize_video_code(code: &str) -> String {
+    let upper = code.trim().to_uppercase();
+    match VIDEO_CODE_RE.captures(&upper) {
+        Some(caps) => {
+            let prefix = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+            format!("{}-{}{}", prefix, &caps[3], &caps[4])
+        }
+        None => upper,
+    }
+}
+
+/// ``true`` when two video codes name the same title (see
+/// ``normalize_video_code``).  Empty codes never match.
+pub fn video_codes_equal(a: &str, b: &str) -> bool {
+    let a = normalize_video_code(a);
+    !a.is_empty() && a == normalize_video_code(b)
+}
+
 pub fn extract_video_code(a_tag: &ElementRef) -> String {
     let sel = Selector::parse("div.video-title").unwrap();
     if let Some(video_title_div) = a_tag.select(&sel).next() {
@@ -116,7 +228,155 @@ pub fn extract_video_code(a_tag: &ElementRef) -> String {
     String::new()
 }
 
+/// Label / navigation text per site locale, used by ``detect_locale``.
+const LOCALE_MARKERS: &[(&str, &[&str])] = &[
+    ("zh", &["番號:", "日期:", "演員:", "類別:", "時長:", "有碼", "無碼", "歐美"]),
+    ("en", &["ID:", "Released Date:", "Actor(s):", "Tags:", "Duration:", "Censored", "Uncensored"]),
+    ("ja", &["品番:", "発売日:", "出演者:", "ジャンル:", "収録時間:", "メーカー:", "ランキング"]),
+];
+
+static HTML_LANG_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)<html[^>]*\slang=["']?([a-z]{2})"#).unwrap());
+
+/// Site locale of a page: ``"zh"``, ``"en"``, ``"ja"`` or ``"unknown"``.
+///
+/// Decided by which locale's panel labels / navigation text occur most
+/// often; the ``<html lang>`` attribute only breaks a tie or a page with
+/// no labels at all.
+pub fn detect_locale(html_content: &str) -> String {
+    let scores: Vec<(&str, usize)> = LOCALE_MARKERS
+        .iter()
+        .map(|(locale, markers)| {
+            (*locale, markers.iter().filter(|m| html_content.contains(**m)).count())
+        })
+        .collect();
+    let best = scores.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    let leaders: Vec<&str> =
+        scores.iter().filter(|(_, n)| *n == best).map(|(l, _)| *l).collect();
+    if best > 0 && leaders.len() == 1 {
+        return leaders[0].to_string();
+    }
+    let lang = HTML_LANG_RE
+        .captures(html_content)
+        .map(|c| c[1].to_lowercase())
+        .filter(|l| LOCALE_MARKERS.iter().any(|(loc, _)| loc == l));
+    match lang {
+        Some(l) if best == 0 || leaders.contains(&l.as_str()) => l,
+        _ => "unknown".to_string(),
+    }
+}
+
+static MHTML_BOUNDARY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)boundary="?([^";\r\n]+)"?"#).unwrap());
+static MHTML_LOCATION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?im)^(?:Snapshot-Content-Location|Content-Location):\s*(\S+)").unwrap()
+});
+
+/// Split a MIME entity into ``(headers, body)`` at the first blank line.
+fn split_mime_headers(part: &str) -> (&str, &str) {
+    for sep in ["\r\n\r\n", "\n\n"] {
+        if let Some(pos) = part.find(sep) {
+            return (&part[..pos], &part[pos + sep.len()..]);
+        }
+    }
+    (part, "")
+}
+
+/// Decode a quoted-printable body: ``=XX`` escapes and ``=`` soft line breaks.
+fn decode_quoted_printable(body: &str) -> String {
+    let bytes = body.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'=' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        match (bytes.get(i + 1), bytes.get(i + 2)) {
+            (Some(b'\r'), Some(b'\n')) => i += 3,
+            (Some(b'\n'), _) => i += 2,
+            (Some(hi), Some(lo)) => {
+                let digit = |b: u8| (b as char).to_digit(16);
+                match (digit(*hi), digit(*lo)) {
+                    (Some(h), Some(l)) => {
+                        out.push((h * 16 + l) as u8);
+                        i += 3;
+                    }
+                    _ => {
+                        out.push(b'=');
+                        i += 1;
+                    }
+                }
+            }
+            _ => {
+                out.push(b'=');
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Decode a base64 body, ignoring the line breaks it is wrapped with.
+fn decode_base64_body(body: &str) -> Option<String> {
+    let compact: String = body.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    let bytes = BASE64.decode(compact).ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Undo the wrapper a browser adds when a page is saved as a single file.
+///
+/// Plain saved HTML (with its ``<!-- saved from url=(NNNN)... -->``
+/// comment) is returned as is.  An MHTML archive ("Save as single file")
+/// is reduced to its HTML part, decoded (quoted-printable or base64), and
+/// prefixed with an equivalent ``saved from`` comment carrying the snapshot
+/// URL, so URL-based page detection keeps working.  A base64 part that
+/// does not decode is skipped.
+pub fn unwrap_saved_page(html_content: &str) -> Cow<'_, str> {
+    let head = html_content.trim_start();
+    if !(head.starts_with("From:") || head.starts_with("MIME-Version:")) {
+        return Cow::Borrowed(html_content);
+    }
+    let (headers, body) = split_mime_headers(head);
+    let Some(boundary) = MHTML_BOUNDARY_RE.captures(headers).map(|c| c[1].to_string()) else {
+        return Cow::Borrowed(html_content);
+    };
+    let location = MHTML_LOCATION_RE.captures(headers).map(|c| c[1].to_string());
+
+    let delimiter = format!("--{boundary}");
+    for part in body.split(delimiter.as_str()) {
+        let (part_headers, part_body) = split_mime_headers(part.trim_start_matches(['\r', '\n']));
+        let lower = part_headers.to_lowercase();
+        if !lower.contains("content-type: text/html") {
+            continue;
+        }
+        let html = if lower.contains("quoted-printable") {
+            decode_quoted_printable(part_body)
+        } else if lower.contains("content-transfer-encoding: base64") {
+            match decode_base64_body(part_body) {
+                Some(html) => html,
+                None => continue,
+            }
+        } else {
+            part_body.to_string()
+        };
+        let url = location.or_else(|| {
+            MHTML_LOCATION_RE
+                .captures(part_headers)
+                .map(|c| c[1].to_string())
+        });
+        return Cow::Owned(match url {
+            Some(url) => format!("<!-- saved from url=({:04}){} -->\n{}", url.len(), url, html),
+            None => html,
+        });
+    }
+    Cow::Borrowed(html_content)
+}
+
 pub fn detect_page_type(html_content: &str) -> String {
+    let unwrapped = unwrap_saved_page(html_content);
+    let html_content: &str = &unwrapped;
     let prefix = if html_content.len() > 3000 {
         &html_content[..3000]
     } else {
@@ -194,6 +454,20 @@ pub fn is_login_page(html_content: &str) -> bool {
     false
 }
 
+/// Whether a fetched page shows the logged-in user menu.
+///
+/// Same markers as the session check in ``javdb/spider/auth/login.py``
+/// (``a[href='/users/edit']`` or ``a[href='/logout']``); a page fetched with
+/// the session cookie that lacks both means the session has expired.
+pub fn extract_session_validity(html_content: &str) -> bool {
+    if html_content.is_empty() {
+        return false;
+    }
+    let document = Html::parse_document(html_content);
+    let user_menu_sel = Selector::parse("a[href='/users/edit'], a[href='/logout']").unwrap();
+    document.select(&user_menu_sel).next().is_some()
+}
+
 /// Validate index page HTML.
 ///
 /// Returns ``(has_movie_list, is_valid_empty_page)``.
@@ -233,17 +507,11 @@ pub fn validate_index_html(html_content: &str) -> (bool, bool) {
 
         // Check for no-content text patterns
         let body_text: String = document.root_element().text().collect();
-        let no_content_patterns = [
-            "No content yet",
-            "No result",
-            "暫無內容",
-            "暂无内容",
-        ];
         let age_modal_sel = Selector::parse("div.modal.is-active.over18-modal").unwrap();
         let has_age_modal = document.select(&age_modal_sel).next().is_some();
 
         if !has_age_modal {
-            for pattern in &no_content_patterns {
+            for pattern in NO_CONTENT_PATTERNS {
                 if body_text.contains(pattern) {
                     return (false, true);
                 }
@@ -257,13 +525,109 @@ pub fn validate_index_html(html_content: &str) -> (bool, bool) {
     (false, false)
 }
 
+/// Whether a detail URL returned JavDB's "page does not exist" notice for a
+/// removed title.  The site serves it with HTTP 200, so the status code
+/// cannot be relied on.  Pages that still carry detail markers are never
+/// treated as removed, whatever their text mentions.
+pub fn is_removed_title(html_content: &str) -> bool {
+    if html_content.is_empty()
+        || html_content.contains("video-meta-panel")
+        || html_content.contains("magnets-content")
+    {
+        return false;
+    }
+    let document = Html::parse_document(html_content);
+    let notice_sel = Selector::parse("title, h1, h2, .title, .message, .notification").unwrap();
+    document.select(&notice_sel).any(|el| {
+        let text = get_text_content(&el).to_lowercase();
+        REMOVED_TITLE_PATTERNS.iter().any(|p| text.contains(p))
+    })
+}
+
+/// Whether the body is Cloudflare's "Error 1020 / Access denied" firewall
+/// block.  Unlike Turnstile this is not a challenge that retries can pass:
+/// the exit IP itself is refused, so the proxy should be switched and
+/// banned straight away.
+pub fn is_cf_access_denied(html_content: &str) -> bool {
+    if html_content.contains("video-meta-panel") || html_content.contains("movie-list") {
+        return false;
+    }
+    let lower = html_content.to_lowercase();
+    if lower.contains("error code: 1020") {
+        return true;
+    }
+    lower.contains("cloudflare") && lower.contains("access denied") && lower.contains("1020")
+}
+
+/// Classify a fetched body for routing: ``"detail"``, ``"index"``,
+/// ``"cf_access_denied"``, ``"turnstile"``, ``"age_modal"``, ``"login"``, ``"removed"``, ``"empty"``,
+/// ``"landing"`` or ``"unknown"``.
+///
+/// ``"empty"`` is a blank body or a listing that says it has no results;
+/// ``"landing"`` is a large page with neither a movie list nor detail
+/// markers (what ``validate_index_html`` accepts as a valid empty page
+/// without any no-content message).  An age modal over real content does
+/// not block parsing, so such pages classify by their content.
+pub fn classify_response(html_content: &str) -> String {
+    if html_content.trim().is_empty() {
+        return "empty".to_string();
+    }
+    if is_cf_access_denied(html_content) {
+        return "cf_access_denied".to_string();
+    }
+    if html_content.contains("Security Verification")
+        && html_content.to_lowercase().contains("turnstile")
+    {
+        return "turnstile".to_string();
+    }
+    if is_login_page(html_content) {
+        return "login".to_string();
+    }
+    if is_removed_title(html_content) {
+        return "removed".to_string();
+    }
+    // Same markers as ``detect_page_type``, checked directly because that
+    // function prefers the canonical-URL category when one is present.
+    if html_content.contains("magnets-content") || html_content.contains("video-meta-panel") {
+        return "detail".to_string();
+    }
+
+    let (has_movie_list, is_valid_empty_page) = validate_index_html(html_content);
+    if has_movie_list {
+        return "index".to_string();
+    }
+    if html_content.contains("over18-modal") {
+        return "age_modal".to_string();
+    }
+    if is_valid_empty_page {
+        let says_empty = html_content.contains("empty-message")
+            || html_content.contains("movie-list")
+            || NO_CONTENT_PATTERNS.iter().any(|p| html_content.contains(p));
+        return if says_empty { "empty" } else { "landing" }.to_string();
+    }
+    "unknown".to_string()
+}
+
 fn class_contains_in_html(el: &ElementRef, substr: &str) -> bool {
     el.value()
         .attr("class")
         .map_or(false, |classes| classes.contains(substr))
 }
 
+/// Maintainer debug switch: ``JAVDB_PARSE_RAW=1`` makes ``get_text_content``
+/// return text nodes exactly as scraped, to expose whitespace while
+/// debugging selectors.  Read once per process; off unless set to ``1``.
+static PARSE_RAW: Lazy<bool> =
+    Lazy::new(|| std::env::var("JAVDB_PARSE_RAW").is_ok_and(|v| v.trim() == "1"));
+
 pub fn get_text_content(el: &ElementRef) -> String {
+    text_content(el, *PARSE_RAW)
+}
+
+fn text_content(el: &ElementRef, raw: bool) -> String {
+    if raw {
+        return el.text().collect();
+    }
     // Mirror BeautifulSoup's `get_text(strip=True)`: strip each text node
     // before joining with the empty separator. Without this, adjacent
     // text nodes around inline tags (e.g. `<strong>code</strong> title`)
@@ -298,6 +662,63 @@ mod tests {
         assert_eq!(comments, "595");
     }
 
+    #[test]
+    fn test_extract_rate_and_comments_english() {
+        let (rate, comments) = extract_rate_and_comments("4.47, by 595 reviews");
+        assert_eq!(rate, "4.47");
+        assert_eq!(comments, "595");
+
+        let (rate, comments) = extract_rate_and_comments("3.9, by 1 user");
+        assert_eq!(rate, "3.9");
+        assert_eq!(comments, "1");
+    }
+
+    #[test]
+    fn test_extract_rate_and_comments_bare_number() {
+        let (rate, comments) = extract_rate_and_comments("  4.47 ");
+        assert_eq!(rate, "4.47");
+        assert_eq!(comments, "");
+
+        let (rate, comments) = extract_rate_and_comments("no score yet");
+        assert_eq!(rate, "");
+        assert_eq!(comments, "");
+    }
+
+    #[test]
+    fn test_resolve_relative_date() {
+        use chrono::TimeZone;
+        let reference = Some(Local.with_ymd_and_hms(2025, 3, 1, 1, 30, 0).unwrap());
+        let resolve = |t: &str| resolve_relative_date(t, reference);
+
+        assert_eq!(resolve("今天").as_deref(), Some("2025-03-01"));
+        assert_eq!(resolve("Today").as_deref(), Some("2025-03-01"));
+        assert_eq!(resolve("昨日").as_deref(), Some("2025-02-28"));
+        assert_eq!(resolve("yesterday").as_deref(), Some("2025-02-28"));
+        assert_eq!(resolve("3天前").as_deref(), Some("2025-02-26"));
+        assert_eq!(resolve("10 days ago").as_deref(), Some("2025-02-19"));
+        assert_eq!(resolve("2小時前").as_deref(), Some("2025-02-28"));
+        assert_eq!(resolve("45 minutes ago").as_deref(), Some("2025-03-01"));
+        assert_eq!(resolve("2025-01-15"), None);
+        assert_eq!(resolve(""), None);
+    }
+
+    #[test]
+    fn test_extract_session_validity() {
+        let logged_in = r#"<html><body><nav class="navbar">
+            <div class="navbar-dropdown">
+              <a class="navbar-item" href="/users/edit">設置</a>
+              <a class="navbar-item" href="/logout" data-method="delete">登出</a>
+            </div></nav><div class="movie-list"></div></body></html>"#;
+        assert!(extract_session_validity(logged_in));
+
+        let logged_out = r#"<html><body><nav class="navbar">
+            <a class="navbar-item" href="/login">登入</a>
+            <a class="navbar-item" href="/users/sign_up">註冊</a>
+            </nav><div class="movie-list"></div></body></html>"#;
+        assert!(!extract_session_validity(logged_out));
+        assert!(!extract_session_validity(""));
+    }
+
     #[test]
     fn test_detect_page_type_detail() {
         assert_eq!(detect_page_type("<div class=\"magnets-content\">"), "detail");
@@ -308,9 +729,182 @@ mod tests {
         assert_eq!(detect_page_type("<div class=\"movie-list\">"), "index");
     }
 
+    #[test]
+    fn test_parse_rate_value() {
+        assert_eq!(parse_rate_value("4.47"), Some(4.47));
+        assert_eq!(parse_rate_value(" 5 "), Some(5.0));
+        assert_eq!(parse_rate_value(""), None);
+        assert_eq!(parse_rate_value("447"), None);
+        assert_eq!(parse_rate_value("-1"), None);
+        assert_eq!(parse_rate_value("NaN"), None);
+    }
+
+    #[test]
+    fn test_text_content_raw_mode() {
+        let html = Html::parse_fragment("<div> <strong>ABC-123</strong>\n  Title </div>");
+        let div = html.select(&Selector::parse("div").unwrap()).next().unwrap();
+        assert_eq!(text_content(&div, false), "ABC-123Title");
+        assert_eq!(text_content(&div, true), " ABC-123\n  Title ");
+        if std::env::var_os("JAVDB_PARSE_RAW").is_none() {
+            assert_eq!(get_text_content(&div), "ABC-123Title");
+        }
+    }
+
+    #[test]
+    fn test_is_removed_title() {
+        let removed = r#"<html><head><title>頁面不存在 | JavDB</title></head><body>
+            <section class="section"><h1 class="title">404</h1>
+            <p class="message">頁面不存在</p></section></body></html>"#;
+        assert!(is_removed_title(removed));
+        assert_eq!(classify_response(removed), "removed");
+
+        let en = "<html><head><title>Page Not Found</title></head><body></body></html>";
+        assert!(is_removed_title(en));
+
+        let detail = r#"<html><head><title>ABC-123 | JavDB</title></head><body>
+            <div class="video-meta-panel"></div>
+            <div class="review">Some comment: page not found on other site</div>
+            </body></html>"#;
+        assert!(!is_removed_title(detail));
+        assert!(!is_removed_title(""));
+    }
+
+    #[test]
+    fn test_classify_response() {
+        let cases = [
+            ("  \n", "empty"),
+            (
+                "<html><title>Just a moment</title><body>Security Verification \
+                 <div class=\"cf-turnstile\"></div></body></html>",
+                "turnstile",
+            ),
+            ("<html><head><title>登入 | JavDB</title></head></html>", "login"),
+            (
+                "<html><body><div class=\"video-meta-panel\"></div></body></html>",
+                "detail",
+            ),
+            (
+                "<html><body><div class=\"movie-list\"><div class=\"item\">A</div></div>\
+                 </body></html>",
+                "index",
+            ),
+            (
+                "<html><body><div class=\"modal is-active over18-modal\">18+</div>\
+                 </body></html>",
+                "age_modal",
+            ),
+            (
+                "<html><body><div class=\"empty-message\">暫無內容</div></body></html>",
+                "empty",
+            ),
+            ("<html><body><p>hello</p></body></html>", "unknown"),
+        ];
+        for (html, expected) in cases {
+            assert_eq!(classify_response(html), expected, "{html}");
+        }
+
+        let landing = format!("<html><body>{}</body></html>", "<p>news</p>".repeat(3000));
+        assert_eq!(classify_response(&landing), "landing");
+    }
+
     #[test]
     fn test_is_login_page_copyright_restriction() {
         let html = "<html><body>Due to copyright restrictions, this page is not available in your country.</body></html>";
         assert!(is_login_page(html));
     }
+
+    #[test]
+    fn test_resolve_url_forms() {
+        let base = "https://javdb.com/actors/abc?page=2";
+        assert_eq!(resolve_url(base, "https://other.com/v/x"), "https://other.com/v/x");
+        assert_eq!(resolve_url(base, "/v/AbC1"), "https://javdb.com/v/AbC1");
+        assert_eq!(resolve_url(base, "//cdn.javdb.com/p/1.jpg"), "https://cdn.javdb.com/p/1.jpg");
+        assert_eq!(resolve_url(base, "def?page=3"), "https://javdb.com/actors/def?page=3");
+        assert_eq!(resolve_url("not a url", "/v/x"), "/v/x");
+    }
+
+    #[test]
+    fn test_video_codes_equal_variants() {
+        assert_eq!(normalize_video_code(" abc-00123 "), "ABC-123");
+        assert!(video_codes_equal("ABC-00123", "abc-123"));
+        assert!(video_codes_equal("abc123", "ABC-123"));
+        assert!(video_codes_equal("FC2-PPV-0123456", "fc2-ppv-123456"));
+        assert!(video_codes_equal("123456-789", "123456-789"));
+        assert_eq!(normalize_video_code("t28-00123"), "T28-123");
+        assert_eq!(normalize_video_code("1pon-010120_001"), "1PON-010120_001");
+        assert_eq!(normalize_video_code("CARIB-010120-001"), "CARIB-010120-001");
+        assert_eq!(normalize_video_code("HEYZO-0123"), "HEYZO-123");
+        assert!(!video_codes_equal("CARIB-010120-001", "CARIB-010120-1"));
+        assert!(!video_codes_equal("ABC-123", "ABC-1234"));
+        assert!(!video_codes_equal("ABC-123", "ABD-123"));
+        assert!(!video_codes_equal("", ""));
+    }
+
+    #[test]
+    fn test_cf_access_denied_1020() {
+        let html = include_str!("../../tests/fixtures/parser/cf_access_denied_1020.html");
+        assert!(is_cf_access_denied(html));
+        assert_eq!(classify_response(html), "cf_access_denied");
+        assert!(is_cf_access_denied("error code: 1020"));
+
+        let turnstile = "<html><title>Security Verification</title><div class=\"cf-turnstile\"></div></html>";
+        assert!(!is_cf_access_denied(turnstile));
+        assert_eq!(classify_response(turnstile), "turnstile");
+    }
+
+    #[test]
+    fn test_detect_locale_from_labels() {
+        let zh = include_str!("../../tests/fixtures/parser/locale_zh_header.html");
+        let en = include_str!("../../tests/fixtures/parser/locale_en_header.html");
+        assert_eq!(detect_locale(zh), "zh");
+        assert_eq!(detect_locale(en), "en");
+        assert_eq!(
+            detect_locale("<html><body><strong>品番:</strong> <strong>発売日:</strong></body></html>"),
+            "ja"
+        );
+        assert_eq!(detect_locale(r#"<html lang="en"><body></body></html>"#), "en");
+        assert_eq!(detect_locale("<html><body>nothing here</body></html>"), "unknown");
+    }
+
+    #[test]
+    fn test_saved_single_file_detail_page() {
+        let saved = include_str!("../../tests/fixtures/parser/detail_saved_single_file.mhtml");
+        assert_eq!(detect_page_type(saved), "detail");
+
+        let html = unwrap_saved_page(saved);
+        assert!(html.starts_with("<!-- saved from url=(0026)https://javdb.com/v/SaVeD1 -->"));
+        assert!(html.contains(r#"<strong class="current-title">SAV-001 Saved Page</strong>"#));
+        assert!(html.contains("番號:"));
+
+        let plain = "<!-- saved from url=(0026)https://javdb.com/v/SaVeD1 --><html></html>";
+        assert!(matches!(unwrap_saved_page(plain), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_saved_page_with_base64_html_part() {
+        let html = r#"<html><body><div class="video-meta-panel">番號:</div></body></html>"#;
+        let encoded = BASE64.encode(html);
+        let (first, rest) = encoded.split_at(40);
+        let saved = format!(
+            "From: <Saved by Blink>\r\n\
+             Snapshot-Content-Location: https://javdb.com/v/B64\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: multipart/related; type=\"text/html\"; boundary=\"----B\"\r\n\r\n\
+             ------B\r\n\
+             Content-Type: text/html\r\n\
+             Content-Transfer-Encoding: base64\r\n\r\n\
+             {first}\r\n{rest}\r\n\
+             ------B--\r\n"
+        );
+        let unwrapped = unwrap_saved_page(&saved);
+        assert_eq!(
+            unwrapped,
+            format!("<!-- saved from url=(0023)https://javdb.com/v/B64 -->\n{html}")
+        );
+        assert_eq!(detect_page_type(&saved), "detail");
+
+        // An undecodable part is skipped rather than returned as garbage.
+        let broken = saved.replace(first, "not*base64");
+        assert!(matches!(unwrap_saved_page(&broken), Cow::Borrowed(_)));
+    }
 }
@@ -1,11 +1,16 @@
-use log::debug;
+use chrono::NaiveDate;
+use log::{debug, warn};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
 
-use crate::models::{ActorCredit, MagnetInfo, MovieDetail, MovieLink};
+use crate::models::{
+    ActorCredit, MagnetInfo, MovieComment, MovieDetail, MovieLink, ParseWarning,
+};
 use crate::scraper::common::{
-    extract_all_movie_links, extract_movie_link, extract_rate_and_comments, get_text_content,
+    class_contains, extract_all_movie_links, extract_movie_link, extract_rate_and_comments,
+    get_text_content, has_class, parse_rate_value, resolve_relative_date, unwrap_saved_page,
 };
 
 static SEL_CURRENT_TITLE: Lazy<Selector> =
@@ -40,34 +45,72 @@ static SEL_PREVIEW_VIDEO: Lazy<Selector> =
 static SEL_SOURCE: Lazy<Selector> = Lazy::new(|| Selector::parse("source").unwrap());
 static SEL_REVIEW_TAB: Lazy<Selector> =
     Lazy::new(|| Selector::parse("a.review-tab").unwrap());
+static SEL_REVIEW_ITEM: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(".review-items .review-item").unwrap());
+static SEL_REVIEW_TITLE: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(".review-title").unwrap());
+static SEL_REVIEW_STAR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(".score-stars i.icon-star").unwrap());
+static SEL_REVIEW_CONTENT: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(".content").unwrap());
+static SEL_REVIEW_LIKES: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(".likes-count").unwrap());
 static SEL_SIZE7: Lazy<Selector> = Lazy::new(|| Selector::parse("span.is-size-7").unwrap());
+static SEL_ACTIVE_TAB: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("li.is-active, a.is-active, button.is-active").unwrap());
 
+static SEL_MOVIE_ANCHOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("a[href^='/v/']").unwrap());
+static SEL_EPISODE_NAV_ANCHOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(".episode-nav a[href^='/v/']").unwrap());
+static SEL_DB_ID: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse("[data-movie-id], [data-video-id], input[name='movie_id']").unwrap()
+});
 static MAGNET_ITEM_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"item columns is-desktop").unwrap());
 static SIZE_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"([\d.]+(?:GB|MB|KB|TB))").unwrap());
 static FILE_COUNT_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(\d+)\s*(?:個文件|files?)").unwrap());
+static SEEDERS_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:做种|做種|seeders?)\s*[:：]?\s*(\d+)").unwrap());
+static LEECHERS_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:下载|下載|leechers?)\s*[:：]?\s*(\d+)").unwrap());
 static REVIEW_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?:短評|Reviews)\((\d+)\)").unwrap());
 static WANT_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(\d+)\s*(?:人想看|want to watch)").unwrap());
+static LEAK_BADGE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:流出|Leaked)\s*[:：]?\s*(\d{4}-\d{2}-\d{2})").unwrap());
+static WORK_COUNT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[(（]\s*(\d[\d,]*)\s*[)）]").unwrap());
 static WATCHED_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(\d+)\s*(?:人看過|have seen)").unwrap());
 
 // Bilingual label sets for panel-block matching.
-// JavDB serves both Traditional Chinese (zh-Hant) and English; Cloudflare
-// bypass paths sometimes return EN. Mirrors apps/api/parsers/detail_parser.py:51-60.
-const L_CODE: &[&str] = &["番號:", "ID:"];
-const L_DATE: &[&str] = &["日期:", "Released Date:"];
-const L_DURATION: &[&str] = &["時長:", "Duration:"];
-const L_DIRECTOR: &[&str] = &["導演:", "Director:"];
-const L_MAKER: &[&str] = &["片商:", "Maker:"];
-const L_PUBLISHER: &[&str] = &["發行商:", "Publisher:"];
-const L_SERIES: &[&str] = &["系列:", "Series:"];
-const L_RATING: &[&str] = &["評分:", "Rating:"];
-const L_TAGS: &[&str] = &["類別:", "Tags:"];
-const L_ACTOR: &[&str] = &["演員:", "Actor(s):"];
+// JavDB serves Traditional Chinese (zh-Hant), English and Japanese; Cloudflare
+// bypass paths sometimes return EN. Each panel label lists its aliases with
+// the Chinese label first. Mirrors apps/api/parsers/detail_parser.py:51-60.
+const L_CODE: &[&str] = &["番號:", "ID:", "品番:"];
+const L_DATE: &[&str] = &["日期:", "Released Date:", "発売日:", "配信日:"];
+const L_DURATION: &[&str] = &["時長:", "Duration:", "収録時間:"];
+const L_DIRECTOR: &[&str] = &["導演:", "Director:", "監督:"];
+const L_MAKER: &[&str] = &["片商:", "Maker:", "メーカー:"];
+const L_PUBLISHER: &[&str] = &["發行商:", "Publisher:", "レーベル:"];
+const L_SERIES: &[&str] = &["系列:", "Series:", "シリーズ:"];
+const L_RATING: &[&str] = &["評分:", "Rating:", "評価:"];
+const L_TAGS: &[&str] = &["類別:", "Tags:", "ジャンル:"];
+const L_ACTOR: &[&str] = &["演員:", "Actor(s):", "出演者:"];
+const L_LEAK: &[&str] = &["流出日期:", "流出:", "Leaked Date:", "Leaked:", "流出日:"];
+const L_HD_BADGE: &[&str] = &["高清", "HD"];
+const L_REQUEST_PROMPT: &[&str] = &["求字幕", "求種", "Request subtitle", "Request torrent"];
+/// Class fragments JavDB uses to pin / highlight a recommended magnet row.
+const MAGNET_HIGHLIGHT_CLASSES: &[&str] = &["highlight", "recommend"];
+const L_ACTOR_ROLE: &[&str] = &["主演", "客串", "Starring", "Guest"];
+const L_PREV_EPISODE: &[&str] = &["上一集", "上一部", "Previous"];
+const L_NEXT_EPISODE: &[&str] = &["下一集", "下一部", "Next"];
+const L_SORT_DATE: &[&str] = &["按日期", "By Date"];
+const L_SORT_SIZE: &[&str] = &["按大小", "By Size"];
 
 fn find_panel_block<'a>(
     panel_blocks: &[ElementRef<'a>],
@@ -77,7 +120,8 @@ fn find_panel_block<'a>(
         .iter()
         .find(|block| {
             block.select(&SEL_STRONG).next().map_or(false, |strong| {
-                let text = get_text_content(&strong);
+                // Japanese pages sometimes use a full-width colon.
+                let text = get_text_content(&strong).replace('：', ":");
                 let trimmed = text.trim();
                 labels.iter().any(|lbl| trimmed == *lbl)
             })
@@ -89,7 +133,85 @@ fn extract_link_from_panel(panel_blocks: &[ElementRef], labels: &[&str]) -> Opti
     let block = find_panel_block(panel_blocks, labels)?;
     let value_span = block.select(&SEL_VALUE).next()?;
     let a_tag = value_span.select(&SEL_A).next()?;
-    extract_movie_link(&a_tag)
+    let mut link = extract_movie_link(&a_tag)?;
+    link.work_count = extract_work_count(&value_span, &link.name);
+    Some(link)
+}
+
+/// Title count such as ``S1 (1234)`` printed after the link text; the link
+/// name itself is skipped so a bracketed number in a name is not mistaken
+/// for the count.
+fn extract_work_count(value_span: &ElementRef, link_name: &str) -> Option<i32> {
+    let text = get_text_content(value_span);
+    let rest = text.split_once(link_name).map_or(text.as_str(), |(_, r)| r);
+    let caps = WORK_COUNT_RE.captures(rest)?;
+    caps[1].replace(',', "").parse().ok()
+}
+
+/// Adjacent-episode link: an ``<a rel="prev|next">`` to another movie, or a
+/// movie link inside ``.episode-nav`` whose text starts with one of the
+/// navigation labels.  The label is dropped from the returned name.
+fn extract_episode_link(document: &Html, rel: &str, labels: &[&str]) -> Option<MovieLink> {
+    fn strip_label<'a>(text: &'a str, labels: &[&str]) -> Option<&'a str> {
+        labels.iter().find_map(|lbl| text.trim().strip_prefix(lbl)).map(str::trim)
+    }
+    let a_tag = document
+        .select(&SEL_MOVIE_ANCHOR)
+        .find(|a| a.value().attr("rel") == Some(rel))
+        .or_else(|| {
+            document
+                .select(&SEL_EPISODE_NAV_ANCHOR)
+                .find(|a| strip_label(&get_text_content(a), labels).is_some())
+        })?;
+    let mut link = extract_movie_link(&a_tag)?;
+    if let Some(name) = strip_label(&link.name, labels).filter(|n| !n.is_empty()) {
+        link.name = name.to_string();
+    }
+    Some(link)
+}
+
+/// Extra score blocks such as ``FANZA評分:`` / ``FANZA Rating:``.  The
+/// primary JavDB block (``L_RATING``) is skipped; each other block whose label
+/// ends in a rating suffix contributes ``source -> rate``.
+fn extract_external_rates(panel_blocks: &[ElementRef]) -> HashMap<String, String> {
+    let mut rates = HashMap::new();
+    for block in panel_blocks {
+        let Some(strong) = block.select(&SEL_STRONG).next() else {
+            continue;
+        };
+        let label = get_text_content(&strong).trim().to_string();
+        if L_RATING.contains(&label.as_str()) {
+            continue;
+        }
+        let Some(source) = L_RATING.iter().find_map(|suffix| label.strip_suffix(suffix)) else {
+            continue;
+        };
+        let source = source.trim();
+        let Some(value_span) = block.select(&SEL_VALUE).next() else {
+            continue;
+        };
+        let (rate, _) = extract_rate_and_comments(get_text_content(&value_span).trim());
+        if !source.is_empty() && !rate.is_empty() {
+            rates.insert(source.to_string(), rate);
+        }
+    }
+    rates
+}
+
+/// Numeric database id carried by ``data-movie-id`` / ``data-video-id`` or a
+/// hidden ``movie_id`` input (review and list forms).  Non-numeric values
+/// are ignored so the ``/v/`` token is never mistaken for it.
+fn extract_db_id(document: &Html) -> Option<String> {
+    document.select(&SEL_DB_ID).find_map(|el| {
+        let attrs = el.value();
+        let value = attrs
+            .attr("data-movie-id")
+            .or_else(|| attrs.attr("data-video-id"))
+            .or_else(|| attrs.attr("value"))?
+            .trim();
+        (!value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| value.to_string())
+    })
 }
 
 fn extract_links_from_panel(panel_blocks: &[ElementRef], labels: &[&str]) -> Vec<MovieLink> {
@@ -126,6 +248,22 @@ fn gender_after_actor(actor: &ElementRef<'_>) -> String {
     String::new()
 }
 
+/// Role label (``主演`` / ``客串``) written between an actor ``<a>`` and the
+/// next one, either as bare text or in a small wrapper element.
+fn role_after_actor(actor: &ElementRef<'_>) -> Option<String> {
+    for sib in actor.next_siblings() {
+        let text = match ElementRef::wrap(sib) {
+            Some(el) if el.value().name() == "a" => break,
+            Some(el) => get_text_content(&el),
+            None => sib.value().as_text().map(|t| t.to_string()).unwrap_or_default(),
+        };
+        if let Some(label) = L_ACTOR_ROLE.iter().find(|l| text.contains(**l)) {
+            return Some(label.to_string());
+        }
+    }
+    None
+}
+
 fn extract_actors_with_gender(panel_blocks: &[ElementRef]) -> Vec<ActorCredit> {
     let block = match find_panel_block(panel_blocks, L_ACTOR) {
         Some(b) => b,
@@ -145,10 +283,12 @@ fn extract_actors_with_gender(panel_blocks: &[ElementRef]) -> Vec<ActorCredit> {
             continue;
         };
         let gender = gender_after_actor(&a_tag);
+        let role = role_after_actor(&a_tag);
         actors.push(ActorCredit {
             name: ml.name,
             href: ml.href,
             gender,
+            role,
         });
     }
     actors
@@ -205,6 +345,20 @@ fn extract_text_from_panel(panel_blocks: &[ElementRef], labels: &[&str]) -> Stri
         .map_or(String::new(), |v| get_text_content(&v).trim().to_string())
 }
 
+/// Uncensored leak date from a labelled panel block, or from a
+/// ``流出 YYYY-MM-DD`` badge inside the meta panel.  ``None`` on most pages.
+fn extract_leak_date(panel: Option<ElementRef>, panel_blocks: &[ElementRef]) -> Option<String> {
+    let labelled = extract_text_from_panel(panel_blocks, L_LEAK);
+    if !labelled.is_empty() {
+        return Some(labelled);
+    }
+    panel?.select(&SEL_TAG_SPAN).find_map(|badge| {
+        LEAK_BADGE_RE
+            .captures(&get_text_content(&badge))
+            .map(|c| c[1].to_string())
+    })
+}
+
 fn parse_magnets(document: &Html) -> (Vec<MagnetInfo>, bool) {
     let magnets_content = match document.select(&SEL_MAGNETS_CONTENT).next() {
         Some(mc) => mc,
@@ -241,19 +395,30 @@ fn parse_magnets(document: &Html) -> (Vec<MagnetInfo>, bool) {
             .next()
             .map_or(String::new(), |s| get_text_content(&s).trim().to_string());
 
-        // Size + file count (both extracted from the same .meta span text)
-        let (size, file_count) = magnet_a
+        // Size, file count and (when shown) seed / peer counts, all from the
+        // same .meta span text
+        let meta_text = magnet_a
             .select(&SEL_META_SPAN)
             .next()
-            .map(|meta| {
-                let meta_text = get_text_content(&meta).trim().to_string();
+            .map(|meta| get_text_content(&meta).trim().to_string());
+        let count_in_meta = |re: &Regex| {
+            meta_text
+                .as_deref()
+                .and_then(|t| re.captures(t))
+                .and_then(|c| c[1].parse::<i32>().ok())
+        };
+        let seeders = count_in_meta(&SEEDERS_RE);
+        let leechers = count_in_meta(&LEECHERS_RE);
+        let (size, file_count) = meta_text
+            .as_deref()
+            .map(|meta_text| {
                 let size = SIZE_RE
-                    .captures(&meta_text)
+                    .captures(meta_text)
                     .and_then(|c| c.get(1))
                     .map(|m| m.as_str().to_string())
                     .unwrap_or_default();
                 let file_count = FILE_COUNT_RE
-                    .captures(&meta_text)
+                    .captures(meta_text)
                     .and_then(|c| c.get(1))
                     .and_then(|m| m.as_str().parse::<u32>().ok())
                     .unwrap_or(0);
@@ -266,6 +431,7 @@ fn parse_magnets(document: &Html) -> (Vec<MagnetInfo>, bool) {
             .select(&SEL_TIME_SPAN)
             .next()
             .map_or(String::new(), |t| get_text_content(&t).trim().to_string());
+        let timestamp = resolve_relative_date(&timestamp, None).unwrap_or(timestamp);
 
         // Tags
         let mut tags = Vec::new();
@@ -278,6 +444,11 @@ fn parse_magnets(document: &Html) -> (Vec<MagnetInfo>, bool) {
             }
         }
 
+        let tracker_count = count_trackers(&magnet_href);
+        let is_hd = tags.iter().any(|t| L_HD_BADGE.contains(&t.as_str()));
+        let is_recommended = MAGNET_HIGHLIGHT_CLASSES
+            .iter()
+            .any(|c| class_contains(&item, c) || class_contains(&magnet_name_div, c));
         magnets.push(MagnetInfo {
             href: magnet_href,
             name,
@@ -285,14 +456,267 @@ fn parse_magnets(document: &Html) -> (Vec<MagnetInfo>, bool) {
             size,
             file_count,
             timestamp,
+            tracker_count,
+            seeders,
+            leechers,
+            is_hd,
+            is_recommended,
+            category: String::new(),
+            downloaded: false,
         });
     }
 
     (magnets, true)
 }
 
+/// Short reviews from the review tab.  JavDB often lazy-loads the tab, in
+/// which case there are no review items and the result is empty.
+fn extract_comments(document: &Html) -> Vec<MovieComment> {
+    document
+        .select(&SEL_REVIEW_ITEM)
+        .map(|item| {
+            let title = item.select(&SEL_REVIEW_TITLE).next();
+            // The author is the leading text of the title row, before the
+            // star / date / likes spans.
+            let author = title
+                .and_then(|t| {
+                    t.children().find_map(|n| {
+                        let text = match ElementRef::wrap(n) {
+                            Some(el) if matches!(el.value().name(), "strong" | "a") => {
+                                get_text_content(&el)
+                            }
+                            Some(_) => return None,
+                            None => n.value().as_text()?.to_string(),
+                        };
+                        let text = text.trim().to_string();
+                        (!text.is_empty()).then_some(text)
+                    })
+                })
+                .unwrap_or_default();
+            let stars: Vec<ElementRef> = item.select(&SEL_REVIEW_STAR).collect();
+            let score = (!stars.is_empty())
+                .then(|| stars.iter().filter(|s| !has_class(s, "gray")).count() as i32);
+            let date = item
+                .select(&SEL_TIME_SPAN)
+                .next()
+                .map(|t| get_text_content(&t).trim().to_string())
+                .unwrap_or_default();
+            let content = item
+                .select(&SEL_REVIEW_CONTENT)
+                .next()
+                .map(|c| get_text_content(&c).trim().to_string())
+                .unwrap_or_default();
+            let helpful_count = item
+                .select(&SEL_REVIEW_LIKES)
+                .next()
+                .and_then(|l| get_text_content(&l).trim().parse::<i32>().ok())
+                .unwrap_or(0);
+            MovieComment {
+                author,
+                score,
+                date,
+                content,
+                helpful_count,
+            }
+        })
+        .collect()
+}
+
+/// Parse the magnet rows of a ``#magnets-content`` fragment (e.g. the HTML
+/// returned when the magnet tab loads separately).  The wrapper div may be
+/// left out of ``magnets_html``.
+pub fn parse_magnets_fragment(magnets_html: &str) -> Vec<MagnetInfo> {
+    let document = Html::parse_document(magnets_html);
+    if document.select(&SEL_MAGNETS_CONTENT).next().is_some() {
+        return parse_magnets(&document).0;
+    }
+    let wrapped = format!(r#"<div id="magnets-content">{magnets_html}</div>"#);
+    parse_magnets(&Html::parse_document(&wrapped)).0
+}
+
+/// Count ``tr=`` tracker parameters in a magnet URI.
+fn count_trackers(magnet_href: &str) -> i32 {
+    let query = match magnet_href.split_once('?') {
+        Some((_, q)) => q,
+        None => return 0,
+    };
+    query
+        .split('&')
+        .filter(|param| param.starts_with("tr=") || param.starts_with("tr."))
+        .count() as i32
+}
+
+/// Why a page has no magnets: ``"request_prompt"`` when it shows a
+/// 求字幕 / request prompt instead, ``"empty"`` when the magnets section is
+/// present but has no items, ``"no_section"`` when there is no section.
+fn magnets_unavailable_reason(document: &Html, has_magnets: bool) -> Option<String> {
+    if has_magnets {
+        return None;
+    }
+    let section = document.select(&SEL_MAGNETS_CONTENT).next();
+    let scope_text = match section {
+        Some(mc) => get_text_content(&mc),
+        None => get_text_content(&document.root_element()),
+    };
+    let reason = if L_REQUEST_PROMPT.iter().any(|p| scope_text.contains(p)) {
+        "request_prompt"
+    } else if section.is_some() {
+        "empty"
+    } else {
+        "no_section"
+    };
+    Some(reason.to_string())
+}
+
+pub const WARN_TITLE_MISSING: &str = "title_missing";
+pub const WARN_VIDEO_CODE_MISSING: &str = "video_code_missing";
+pub const WARN_MAGNETS_MISSING: &str = "magnets_missing";
+
+/// Structural problems worth alerting on.  A page that shows a request
+/// prompt or an empty magnets list is normal and not reported.
+fn collect_warnings(detail: &MovieDetail) -> Vec<ParseWarning> {
+    let mut warnings = Vec::new();
+    if detail.title.is_empty() {
+        warnings.push(ParseWarning::new(WARN_TITLE_MISSING, "no current-title element"));
+    }
+    if detail.video_code.is_empty() {
+        warnings.push(ParseWarning::new(WARN_VIDEO_CODE_MISSING, "no video code in meta panel"));
+    }
+    if detail.magnets_unavailable_reason.as_deref() == Some("no_section") {
+        warnings.push(ParseWarning::new(WARN_MAGNETS_MISSING, "no #magnets-content section"));
+    }
+    for w in &warnings {
+        warn!("Detail parse warning [{}]: {}", w.code, w.message);
+    }
+    warnings
+}
+
+/// Server-side magnet order ("date" / "size") from the active sort tab.
+///
+/// ``None`` when the page shows no sort tabs, in which case the magnets are
+/// in the site's default order.
+fn parse_magnets_sort(document: &Html) -> Option<String> {
+    document.select(&SEL_ACTIVE_TAB).find_map(|tab| {
+        let text = get_text_content(&tab);
+        if L_SORT_DATE.iter().any(|l| text.contains(l)) {
+            Some("date".to_string())
+        } else if L_SORT_SIZE.iter().any(|l| text.contains(l)) {
+            Some("size".to_string())
+        } else {
+            None
+        }
+    })
+}
+
 pub fn parse_detail_page(html_content: &str) -> MovieDetail {
-    let document = Html::parse_document(html_content);
+    let document = Html::parse_document(&unwrap_saved_page(html_content));
+    parse_detail_document(&document)
+}
+
+/// Default day-one window for ``has_dayone_magnet``.
+pub const DEFAULT_DAYONE_WINDOW_DAYS: i64 = 3;
+
+/// Set ``has_dayone_magnet``: some magnet's date is within ``window_days``
+/// of ``release_date`` (either side, since magnet dates are site-local).
+/// Magnets or releases without a parseable ``YYYY-MM-DD`` date never count.
+pub fn mark_dayone_magnet(detail: &mut MovieDetail, window_days: i64) {
+    let parse = |s: &str| NaiveDate::parse_from_str(s.get(..10).unwrap_or(s), "%Y-%m-%d").ok();
+    detail.has_dayone_magnet = match parse(&detail.release_date) {
+        Some(release) => detail.magnets.iter().any(|m| {
+            parse(&m.timestamp).is_some_and(|d| (d - release).num_days().abs() <= window_days)
+        }),
+        None => false,
+    };
+}
+
+/// Keep at most ``max_fanart`` sample image URLs; ``sample_image_count``
+/// still reports how many the page had.  ``None`` keeps them all.
+pub fn limit_fanart(detail: &mut MovieDetail, max_fanart: Option<usize>) {
+    if let Some(max) = max_fanart {
+        detail.fanart_urls.truncate(max);
+    }
+}
+
+/// Parse a detail page and also report which key selectors matched.
+///
+/// A mostly-empty ``MovieDetail`` says nothing about *why* it is empty; the
+/// health map names the selector that stopped matching (``title``, ``code``,
+/// ``magnets``, ``actors``, ...) so layout changes show up as data.
+pub fn parse_detail_page_diagnostic(html_content: &str) -> (MovieDetail, HashMap<String, bool>) {
+    let document = Html::parse_document(&unwrap_saved_page(html_content));
+    let detail = parse_detail_document(&document);
+    (detail, selector_health(&document))
+}
+
+/// Key fields checked by ``detail_completeness``; all required by default.
+pub const COMPLETENESS_FIELDS: &[&str] =
+    &["title", "video_code", "release_date", "magnets", "poster_url"];
+
+/// Report which key fields of a parsed detail are populated, plus
+/// ``is_complete``: every field in ``required`` is populated (defaults to
+/// ``COMPLETENESS_FIELDS``).  Unknown names in ``required`` count as
+/// missing, so a typo fails closed instead of passing weak scrapes.
+pub fn detail_completeness(
+    detail: &MovieDetail,
+    required: Option<&[String]>,
+) -> HashMap<String, bool> {
+    let mut report = HashMap::new();
+    report.insert("title".to_string(), !detail.title.trim().is_empty());
+    report.insert("video_code".to_string(), !detail.video_code.trim().is_empty());
+    report.insert("release_date".to_string(), !detail.release_date.trim().is_empty());
+    report.insert("magnets".to_string(), !detail.magnets.is_empty());
+    report.insert("poster_url".to_string(), !detail.poster_url.trim().is_empty());
+
+    let is_complete = match required {
+        Some(fields) => fields.iter().all(|f| report.get(f).copied().unwrap_or(false)),
+        None => COMPLETENESS_FIELDS.iter().all(|f| report[*f]),
+    };
+    report.insert("is_complete".to_string(), is_complete);
+    report
+}
+
+fn selector_health(document: &Html) -> HashMap<String, bool> {
+    let video_meta_panel = document.select(&SEL_VIDEO_META_PANEL).next();
+    let panel_blocks: Vec<ElementRef> = video_meta_panel
+        .map(|p| p.select(&SEL_PANEL_BLOCK).collect())
+        .unwrap_or_default();
+    let has_block = |labels: &[&str]| find_panel_block(&panel_blocks, labels).is_some();
+
+    let mut health = HashMap::new();
+    health.insert(
+        "title".to_string(),
+        document.select(&SEL_CURRENT_TITLE).next().is_some(),
+    );
+    health.insert("meta_panel".to_string(), video_meta_panel.is_some());
+    health.insert("code".to_string(), has_block(L_CODE));
+    health.insert("release_date".to_string(), has_block(L_DATE));
+    health.insert("duration".to_string(), has_block(L_DURATION));
+    health.insert("maker".to_string(), has_block(L_MAKER));
+    health.insert("rating".to_string(), has_block(L_RATING));
+    health.insert("tags".to_string(), has_block(L_TAGS));
+    health.insert("actors".to_string(), has_block(L_ACTOR));
+    health.insert(
+        "poster".to_string(),
+        video_meta_panel
+            .and_then(|p| p.select(&SEL_COVER_IMG).next())
+            .is_some(),
+    );
+    health.insert(
+        "fanart".to_string(),
+        document.select(&SEL_TILE_IMAGES).next().is_some(),
+    );
+    health.insert(
+        "magnets".to_string(),
+        document.select(&SEL_MAGNETS_CONTENT).next().is_some(),
+    );
+    health.insert(
+        "review_tab".to_string(),
+        document.select(&SEL_REVIEW_TAB).next().is_some(),
+    );
+    health
+}
+
+fn parse_detail_document(document: &Html) -> MovieDetail {
     let mut detail = MovieDetail::default();
 
     // Title
@@ -317,9 +741,12 @@ pub fn parse_detail_page(html_content: &str) -> MovieDetail {
         }
     }
 
+    detail.db_id = extract_db_id(document);
+
     // Release date, Duration
     detail.release_date = extract_text_from_panel(&panel_blocks, L_DATE);
     detail.duration = extract_text_from_panel(&panel_blocks, L_DURATION);
+    detail.leak_date = extract_leak_date(video_meta_panel, &panel_blocks);
 
     // Directors, Maker, Publisher, Series
     detail.directors = extract_links_from_panel(&panel_blocks, L_DIRECTOR);
@@ -327,15 +754,21 @@ pub fn parse_detail_page(html_content: &str) -> MovieDetail {
     detail.publisher = extract_link_from_panel(&panel_blocks, L_PUBLISHER);
     detail.series = extract_link_from_panel(&panel_blocks, L_SERIES);
 
+    // Previous / next episode navigation
+    detail.prev_link = extract_episode_link(document, "prev", L_PREV_EPISODE);
+    detail.next_link = extract_episode_link(document, "next", L_NEXT_EPISODE);
+
     // Rating & comment count
     if let Some(rating_block) = find_panel_block(&panel_blocks, L_RATING) {
         if let Some(value_span) = rating_block.select(&SEL_VALUE).next() {
             let score_text = get_text_content(&value_span).trim().to_string();
             let (r, c) = extract_rate_and_comments(&score_text);
+            detail.rate_value = parse_rate_value(&r);
             detail.rate = r;
             detail.comment_count = c;
         }
     }
+    detail.external_rates = extract_external_rates(&panel_blocks);
 
     // Tags, Actors (with ♀/♂ markers)
     detail.tags = extract_links_from_panel(&panel_blocks, L_TAGS);
@@ -366,6 +799,7 @@ pub fn parse_detail_page(html_content: &str) -> MovieDetail {
             }
         }
     }
+    detail.sample_image_count = detail.fanart_urls.len();
 
     // Trailer URL
     if let Some(preview_container) = document.select(&SEL_PREVIEW_CONTAINER).next() {
@@ -397,6 +831,7 @@ pub fn parse_detail_page(html_content: &str) -> MovieDetail {
             }
         }
     }
+    detail.comments = extract_comments(document);
 
     // Want/Watched counts
     for block in &panel_blocks {
@@ -416,9 +851,14 @@ pub fn parse_detail_page(html_content: &str) -> MovieDetail {
     }
 
     // Magnets
-    let (magnets, parse_success) = parse_magnets(&document);
+    let (magnets, parse_success) = parse_magnets(document);
+    detail.magnets_unavailable_reason =
+        magnets_unavailable_reason(document, !magnets.is_empty());
     detail.magnets = magnets;
+    detail.magnets_sort = parse_magnets_sort(document);
     detail.parse_success = parse_success;
+    mark_dayone_magnet(&mut detail, DEFAULT_DAYONE_WINDOW_DAYS);
+    detail.warnings = collect_warnings(&detail);
 
     let title_preview: String = detail.title.chars().take(40).collect();
     let title_display = if detail.title.chars().count() > 40 {
@@ -436,3 +876,456 @@ pub fn parse_detail_page(html_content: &str) -> MovieDetail {
 
     detail
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dayone_magnet_window() {
+        let html = include_str!("../../tests/fixtures/parser/detail_dayone_magnets.html");
+        let mut detail = parse_detail_page(html);
+        assert_eq!(detail.release_date, "2026-02-10");
+        assert_eq!(detail.magnets.len(), 2);
+        assert!(detail.has_dayone_magnet);
+
+        mark_dayone_magnet(&mut detail, 0);
+        assert!(!detail.has_dayone_magnet);
+
+        detail.magnets.remove(0);
+        mark_dayone_magnet(&mut detail, DEFAULT_DAYONE_WINDOW_DAYS);
+        assert!(!detail.has_dayone_magnet);
+    }
+
+    #[test]
+    fn test_limit_fanart_keeps_true_count() {
+        let tiles: String = (1..=20)
+            .map(|n| {
+                format!(r#"<a class="tile-item" href="https://c0.jdbstatic.com/samples/ab/abc_l_{n}.jpg"></a>"#)
+            })
+            .collect();
+        let html = format!(
+            r#"<html><body><strong class="current-title">ABC-123 Title</strong>
+               <div class="tile-images preview-images">{tiles}</div></body></html>"#
+        );
+        let mut detail = parse_detail_page(&html);
+        assert_eq!(detail.fanart_urls.len(), 20);
+        assert_eq!(detail.sample_image_count, 20);
+
+        limit_fanart(&mut detail, Some(5));
+        assert_eq!(detail.fanart_urls.len(), 5);
+        assert!(detail.fanart_urls[4].ends_with("abc_l_5.jpg"));
+        assert_eq!(detail.sample_image_count, 20);
+
+        limit_fanart(&mut detail, None);
+        assert_eq!(detail.fanart_urls.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_saved_single_file_detail() {
+        let saved = include_str!("../../tests/fixtures/parser/detail_saved_single_file.mhtml");
+        let detail = parse_detail_page(saved);
+        assert_eq!(detail.title, "SAV-001 Saved Page");
+        assert_eq!(detail.video_code, "SAV-001");
+        assert_eq!(detail.release_date, "2026-05-06");
+        assert_eq!(detail.magnets.len(), 1);
+        assert!(detail.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_english_locale_detail() {
+        let html = include_str!("../../tests/fixtures/parser/detail_en_locale.html");
+        let detail = parse_detail_page(html);
+        assert_eq!(detail.video_code, "ENG-042");
+        assert_eq!(detail.release_date, "2026-04-05");
+        assert_eq!(detail.maker.unwrap().name, "Maker EN");
+        assert_eq!(detail.actors.len(), 1);
+
+        let ja = html
+            .replace("ID:", "品番：")
+            .replace("Released Date:", "発売日:")
+            .replace("Maker:", "メーカー:");
+        let detail = parse_detail_page(&ja);
+        assert_eq!(detail.video_code, "ENG-042");
+        assert_eq!(detail.release_date, "2026-04-05");
+        assert_eq!(detail.maker.unwrap().name, "Maker EN");
+    }
+
+    #[test]
+    fn test_extract_comments_from_review_tab() {
+        let html = r#"<html><body>
+            <strong class="current-title">Some Title</strong>
+            <a class="review-tab">短評(2)</a>
+            <div id="reviews"><dl class="review-items">
+              <dt class="review-item">
+                <div class="review-title">viewer_one&nbsp;
+                  <span class="score-stars"><i class="icon-star"></i><i class="icon-star"></i><i class="icon-star"></i><i class="icon-star gray"></i><i class="icon-star gray"></i></span>
+                  <span class="time">2024-05-06</span>
+                  <span class="likes"><span class="likes-count">12</span></span>
+                </div>
+                <div class="content"><p>Worth it.</p></div>
+              </dt>
+              <dt class="review-item">
+                <div class="review-title"><strong>viewer_two</strong>
+                  <span class="time">2024-05-07</span>
+                </div>
+                <div class="content"><p>No stars given.</p></div>
+              </dt>
+            </dl></div>
+            <div id="magnets-content">
+              <div class="item columns is-desktop">
+                <div class="magnet-name column is-four-fifths">
+                  <a href="magnet:?xt=urn:btih:abc"><span class="name">ABC-123</span></a>
+                </div>
+              </div>
+            </div>
+        </body></html>"#;
+        let detail = parse_detail_page(html);
+        assert_eq!(detail.review_count, 2);
+        assert_eq!(detail.comments.len(), 2);
+        let first = &detail.comments[0];
+        assert_eq!(first.author, "viewer_one");
+        assert_eq!(first.score, Some(3));
+        assert_eq!(first.date, "2024-05-06");
+        assert_eq!(first.content, "Worth it.");
+        assert_eq!(first.helpful_count, 12);
+        let second = &detail.comments[1];
+        assert_eq!((second.author.as_str(), second.score), ("viewer_two", None));
+        assert_eq!(second.helpful_count, 0);
+
+        // Reviews not loaded yet: no comments, but the page still parses.
+        let lazy = parse_detail_page(&html.replace("review-items", "review-placeholder"));
+        assert!(lazy.comments.is_empty());
+        assert!(lazy.parse_success);
+        assert_eq!(lazy.magnets.len(), 1);
+    }
+
+    #[test]
+    fn test_actor_role_labels() {
+        let html = include_str!("../../tests/fixtures/parser/detail_actor_roles.html");
+        let detail = parse_detail_page(html);
+        let credits: Vec<(&str, &str, Option<&str>)> = detail
+            .actors
+            .iter()
+            .map(|a| (a.name.as_str(), a.gender.as_str(), a.role.as_deref()))
+            .collect();
+        assert_eq!(
+            credits,
+            [
+                ("Lead Actress", "female", Some("主演")),
+                ("Guest Actress", "female", Some("客串")),
+                ("Unlabeled Actor", "male", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_detail_page_diagnostic_partial_page() {
+        let html = r#"<html><body>
+            <strong class="current-title">Some Title</strong>
+            <div class="video-meta-panel">
+              <div class="panel-block"><strong>番號:</strong><span class="value">ABC-123</span></div>
+            </div>
+        </body></html>"#;
+        let (detail, health) = parse_detail_page_diagnostic(html);
+        assert_eq!(detail.video_code, "ABC-123");
+        assert!(!detail.parse_success);
+        assert!(health["title"]);
+        assert!(health["meta_panel"]);
+        assert!(health["code"]);
+        assert!(!health["actors"]);
+        assert!(!health["magnets"]);
+
+        let codes: Vec<&str> = detail.warnings.iter().map(|w| w.code.as_str()).collect();
+        assert_eq!(codes, [WARN_MAGNETS_MISSING]);
+    }
+
+    #[test]
+    fn test_detail_completeness_on_partial_detail() {
+        let html = r#"<html><body>
+            <strong class="current-title">Some Title</strong>
+            <div class="video-meta-panel">
+              <div class="panel-block"><strong>番號:</strong><span class="value">ABC-123</span></div>
+              <div class="panel-block"><strong>日期:</strong><span class="value">2024-01-01</span></div>
+            </div>
+        </body></html>"#;
+        let detail = parse_detail_page(html);
+        let report = detail_completeness(&detail, None);
+        assert!(report["title"]);
+        assert!(report["video_code"]);
+        assert!(report["release_date"]);
+        assert!(!report["magnets"]);
+        assert!(!report["poster_url"]);
+        assert!(!report["is_complete"]);
+
+        let relaxed = vec!["title".to_string(), "video_code".to_string()];
+        assert!(detail_completeness(&detail, Some(&relaxed))["is_complete"]);
+        let typo = vec!["titel".to_string()];
+        assert!(!detail_completeness(&detail, Some(&typo))["is_complete"]);
+    }
+
+    #[test]
+    fn test_magnets_unavailable_reason() {
+        let no_section = r#"<html><body><strong class="current-title">T</strong></body></html>"#;
+        let detail = parse_detail_page(no_section);
+        assert!(!detail.parse_success);
+        assert_eq!(detail.magnets_unavailable_reason.as_deref(), Some("no_section"));
+
+        let empty = r#"<html><body><div id="magnets-content"></div></body></html>"#;
+        let detail = parse_detail_page(empty);
+        assert!(detail.parse_success);
+        assert_eq!(detail.magnets_unavailable_reason.as_deref(), Some("empty"));
+
+        let prompt = r#"<html><body><div id="magnets-content">
+            <div class="empty-message">暫無磁鏈
+              <a class="button is-small" href="/v/abc/request">求字幕</a>
+            </div></div></body></html>"#;
+        let detail = parse_detail_page(prompt);
+        assert_eq!(detail.magnets_unavailable_reason.as_deref(), Some("request_prompt"));
+
+        let prompt_no_tab = r#"<html><body>
+            <div class="notification">暫無磁鏈 <a href="/v/abc/request">求種</a></div>
+            </body></html>"#;
+        let detail = parse_detail_page(prompt_no_tab);
+        assert!(!detail.parse_success);
+        assert_eq!(detail.magnets_unavailable_reason.as_deref(), Some("request_prompt"));
+    }
+
+    #[test]
+    fn test_parse_label_work_count() {
+        let html = r#"<html><body>
+            <div class="video-meta-panel">
+              <div class="panel-block"><strong>片商:</strong><span class="value">
+                <a href="/makers/7R">S1 NO.1 STYLE</a> <span class="is-size-7">(1,234)</span>
+              </span></div>
+              <div class="panel-block"><strong>發行商:</strong><span class="value">
+                <a href="/publishers/x">Label (2)</a>
+              </span></div>
+              <div class="panel-block"><strong>系列:</strong><span class="value">
+                <a href="/series/y">Series</a>（56）
+              </span></div>
+            </div>
+        </body></html>"#;
+        let detail = parse_detail_page(html);
+        let maker = detail.maker.unwrap();
+        assert_eq!(maker.href, "/makers/7R");
+        assert_eq!(maker.work_count, Some(1234));
+        assert_eq!(detail.publisher.unwrap().work_count, None);
+        assert_eq!(detail.series.unwrap().work_count, Some(56));
+    }
+
+    #[test]
+    fn test_parse_episode_navigation() {
+        let html = r#"<html><body>
+            <strong class="current-title">Episode 2</strong>
+            <div class="video-meta-panel">
+              <div class="panel-block"><strong>番號:</strong><span class="value">ABC-002</span></div>
+            </div>
+            <nav class="episode-nav">
+              <a href="/v/ep1">上一集 ABC-001</a>
+              <a rel="next" href="/v/ep3">ABC-003</a>
+            </nav>
+        </body></html>"#;
+        let detail = parse_detail_page(html);
+        let prev = detail.prev_link.unwrap();
+        assert_eq!(prev.href, "/v/ep1");
+        assert_eq!(prev.name, "ABC-001");
+        assert_eq!(detail.next_link.unwrap().href, "/v/ep3");
+
+        // Label-like text outside the episode nav is not an episode link.
+        let related = r#"<html><body>
+            <div class="video-meta-panel">
+              <div class="panel-block"><strong>番號:</strong><span class="value">XYZ-002</span></div>
+            </div>
+            <div class="tile-list"><a href="/v/other">Next Door Story</a></div>
+        </body></html>"#;
+        assert!(parse_detail_page(related).next_link.is_none());
+
+        let standalone = r#"<html><body>
+            <div class="video-meta-panel">
+              <div class="panel-block"><strong>番號:</strong><span class="value">XYZ-001</span></div>
+            </div>
+            <a href="/v/other">Related movie</a>
+        </body></html>"#;
+        let detail = parse_detail_page(standalone);
+        assert!(detail.prev_link.is_none());
+        assert!(detail.next_link.is_none());
+    }
+
+    #[test]
+    fn test_parse_external_rates() {
+        let html = r#"<html><body>
+            <div class="video-meta-panel">
+              <div class="panel-block"><strong>評分:</strong>
+                <span class="value">4.47分, 由595人評價</span></div>
+              <div class="panel-block"><strong>FANZA評分:</strong>
+                <span class="value">4.12分</span></div>
+              <div class="panel-block"><strong>DMM Rating:</strong>
+                <span class="value">3.9</span></div>
+            </div>
+        </body></html>"#;
+        let detail = parse_detail_page(html);
+        assert_eq!(detail.rate, "4.47");
+        assert_eq!(detail.rate_value, Some(4.47));
+        assert_eq!(detail.comment_count, "595");
+        assert_eq!(detail.external_rates.len(), 2);
+        assert_eq!(detail.external_rates["FANZA"], "4.12");
+        assert_eq!(detail.external_rates["DMM"], "3.9");
+
+        let single = r#"<html><body><div class="video-meta-panel">
+            <div class="panel-block"><strong>評分:</strong><span class="value">4.0分</span></div>
+        </div></body></html>"#;
+        assert!(parse_detail_page(single).external_rates.is_empty());
+    }
+
+    #[test]
+    fn test_parse_db_id() {
+        let html = r#"<html><body>
+            <div class="video-detail" data-video-id="ZNdEbV">
+              <div class="video-meta-panel">
+                <div class="panel-block"><strong>番號:</strong><span class="value">ABC-123</span></div>
+              </div>
+              <form class="review-form"><input type="hidden" name="movie_id" value="482913"></form>
+            </div>
+        </body></html>"#;
+        assert_eq!(parse_detail_page(html).db_id.as_deref(), Some("482913"));
+
+        let attr = r#"<html><body><div data-movie-id="77"></div></body></html>"#;
+        assert_eq!(parse_detail_page(attr).db_id.as_deref(), Some("77"));
+
+        let absent = r#"<html><body><div class="video-meta-panel"></div></body></html>"#;
+        assert_eq!(parse_detail_page(absent).db_id, None);
+    }
+
+    #[test]
+    fn test_parse_leak_date() {
+        let html = r#"<html><body>
+            <div class="video-meta-panel">
+              <div class="panel-block"><strong>番號:</strong><span class="value">ABC-123</span></div>
+              <div class="panel-block"><strong>日期:</strong><span class="value">2019-05-01</span></div>
+              <div class="panel-block"><strong>流出日期:</strong><span class="value">2023-11-20</span></div>
+            </div>
+        </body></html>"#;
+        let detail = parse_detail_page(html);
+        assert_eq!(detail.release_date, "2019-05-01");
+        assert_eq!(detail.leak_date.as_deref(), Some("2023-11-20"));
+
+        let badge = r#"<div class="video-meta-panel">
+              <div class="panel-block"><strong>日期:</strong><span class="value">2019-05-01</span></div>
+              <span class="tag is-warning">流出 2024-02-03</span>
+            </div>"#;
+        assert_eq!(parse_detail_page(badge).leak_date.as_deref(), Some("2024-02-03"));
+
+        let plain = r#"<div class="video-meta-panel">
+              <div class="panel-block"><strong>日期:</strong><span class="value">2019-05-01</span></div>
+            </div>"#;
+        assert_eq!(parse_detail_page(plain).leak_date, None);
+    }
+
+    #[test]
+    fn test_parse_magnet_hd_badge() {
+        let item = |href: &str, name: &str, tags: &str| {
+            format!(
+                r#"<div class="item columns is-desktop">
+                  <div class="magnet-name"><a href="{href}">
+                    <span class="name">{name}</span><span class="meta">5.10GB, 1個文件</span>
+                    <div class="tags">{tags}</div>
+                  </a></div>
+                </div>"#
+            )
+        };
+        let html = format!(
+            r#"<div id="magnets-content">{}{}</div>"#,
+            item(
+                "magnet:?xt=urn:btih:aaa",
+                "ABC-123",
+                r#"<span class="tag is-primary is-small is-light">高清</span>
+                   <span class="tag is-warning is-small is-light">字幕</span>"#
+            ),
+            item("magnet:?xt=urn:btih:bbb", "ABC-123-1080p", ""),
+        );
+        let detail = parse_detail_page(&html);
+        assert_eq!(detail.magnets.len(), 2);
+        assert!(detail.magnets[0].is_hd);
+        assert_eq!(detail.magnets[0].tags, vec!["高清", "字幕"]);
+        assert!(!detail.magnets[1].is_hd);
+    }
+
+    #[test]
+    fn test_parse_magnet_seed_counts() {
+        let item = |href: &str, meta: &str| {
+            format!(
+                r#"<div class="item columns is-desktop">
+                  <div class="magnet-name"><a href="{href}">
+                    <span class="name">ABC-123</span><span class="meta">{meta}</span>
+                  </a></div>
+                </div>"#
+            )
+        };
+        let html = format!(
+            r#"<div id="magnets-content">{}{}</div>"#,
+            item("magnet:?xt=urn:btih:aaa", "5.10GB, 1個文件"),
+            item("magnet:?xt=urn:btih:bbb", "4.20GB, 2個文件, 做种 12, 下载 3"),
+        );
+        let detail = parse_detail_page(&html);
+        let plain = &detail.magnets[0];
+        assert_eq!((plain.size.as_str(), plain.file_count), ("5.10GB", 1));
+        assert_eq!((plain.seeders, plain.leechers), (None, None));
+        let seeded = &detail.magnets[1];
+        assert_eq!((seeded.size.as_str(), seeded.file_count), ("4.20GB", 2));
+        assert_eq!((seeded.seeders, seeded.leechers), (Some(12), Some(3)));
+    }
+
+    #[test]
+    fn test_parse_recommended_magnet() {
+        let html = r#"<div id="magnets-content">
+            <div class="item columns is-desktop">
+              <div class="magnet-name"><a href="magnet:?xt=urn:btih:aaa">
+                <span class="name">ABC-123</span><span class="meta">5.10GB</span></a></div>
+            </div>
+            <div class="item columns is-desktop is-highlighted">
+              <div class="magnet-name"><a href="magnet:?xt=urn:btih:bbb">
+                <span class="name">ABC-123-C</span><span class="meta">4.20GB</span></a></div>
+            </div>
+        </div>"#;
+        let detail = parse_detail_page(html);
+        assert_eq!(detail.magnets.len(), 2);
+        assert!(!detail.magnets[0].is_recommended);
+        assert!(detail.magnets[1].is_recommended);
+    }
+
+    #[test]
+    fn test_count_trackers() {
+        let href = "magnet:?xt=urn:btih:abc&dn=ABC-123\
+                    &tr=udp%3A%2F%2Fa.example%3A80\
+                    &tr=udp%3A%2F%2Fb.example%3A80\
+                    &tr=http%3A%2F%2Fc.example%2Fannounce";
+        assert_eq!(count_trackers(href), 3);
+        assert_eq!(count_trackers("magnet:?xt=urn:btih:abc"), 0);
+        assert_eq!(count_trackers(""), 0);
+    }
+
+    #[test]
+    fn test_parse_magnets_sort_from_active_tab() {
+        let html = r#"<html><body>
+            <div class="tabs is-small"><ul>
+              <li><a href="?sort_by=size">按大小</a></li>
+              <li class="is-active"><a href="?sort_by=date">按日期</a></li>
+            </ul></div>
+            <div id="magnets-content">
+              <div class="item columns is-desktop">
+                <div class="magnet-name"><a href="magnet:?xt=urn:btih:abc">
+                  <span class="name">ABC-123</span><span class="meta">4.20GB, 1個文件</span>
+                </a></div>
+                <span class="time">2024-01-02</span>
+              </div>
+            </div>
+        </body></html>"#;
+        let detail = parse_detail_page(html);
+        assert_eq!(detail.magnets.len(), 1);
+        assert_eq!(detail.magnets_sort.as_deref(), Some("date"));
+
+        let plain = parse_detail_page(r#"<div id="magnets-content"></div>"#);
+        assert_eq!(plain.magnets_sort, None);
+    }
+}
@@ -2,13 +2,17 @@ use log::{debug, warn};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
 
 use crate::models::{
-    CategoryPageResult, IndexPageResult, MovieIndexEntry, TopPageResult,
+    ActorIndexEntry, ActorPageResult, CategoryPageResult, IndexPageResult, MovieIndexEntry,
+    MovieLink, TopPageResult,
 };
 use crate::scraper::common::{
-    class_contains, detect_page_type, extract_category_name, extract_rate_and_comments,
-    extract_video_code, get_text_content,
+    class_contains, detect_page_type, extract_category_name, extract_movie_link,
+    extract_rate_and_comments,
+    extract_video_code, get_text_content, has_class, normalize_video_code, parse_rate_value,
+    resolve_relative_date, unwrap_saved_page,
 };
 
 static SEL_TITLE: Lazy<Selector> = Lazy::new(|| Selector::parse("title").unwrap());
@@ -26,10 +30,74 @@ static SEL_TAGS_ADDONS: Lazy<Selector> =
 static SEL_TAG_SPAN: Lazy<Selector> = Lazy::new(|| Selector::parse("span.tag").unwrap());
 static SEL_IMG: Lazy<Selector> = Lazy::new(|| Selector::parse("img").unwrap());
 static SEL_RANKING_SPAN: Lazy<Selector> = Lazy::new(|| Selector::parse("span.ranking").unwrap());
+static SEL_SECTION_META: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("span.section-meta").unwrap());
+static SEL_ACTOR_ITEM: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("div.item, div.actor-box").unwrap());
+static SEL_ACTOR_LINK: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("a[href*='/actors/']").unwrap());
+static SEL_STRONG: Lazy<Selector> = Lazy::new(|| Selector::parse("strong").unwrap());
+static SEL_SECTION_HEADER: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse(".section-title, .section-description, .section-addition").unwrap()
+});
+static SEL_SECTION_DESCRIPTION: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(".section-description").unwrap());
+static SEL_MAKER_LINK: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("a[href*='/makers/']").unwrap());
+static SEL_PARAGRAPH: Lazy<Selector> = Lazy::new(|| Selector::parse("p").unwrap());
 
+static SEL_PAGINATION_NEXT: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("nav.pagination a.pagination-next").unwrap());
+static SEL_PAGINATION_ITEM: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse("nav.pagination a.pagination-link, nav.pagination .pagination-ellipsis")
+        .unwrap()
+});
+static SEL_PAGINATION_CURRENT: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("nav.pagination a.pagination-link.is-current").unwrap());
 static YEAR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[?&]t=y(\d{4})").unwrap());
 static PERIOD_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"[?&]p=(daily|weekly|monthly)").unwrap());
+static DISC_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|[\s\-_\[(])(?:cd|disc|disk)\s*[-_]?(\d{1,2})(?:$|[\s\])])").unwrap()
+});
+static DISC_SUFFIX_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s*-(\d)$").unwrap());
+static TOTAL_RESULTS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d[\d,]*)\s*(?:部影片|部|movies?|videos?|titles?)").unwrap()
+});
+
+/// Disc number of a multi-disc release tile (``CD1`` / ``Disc 2`` in the
+/// title or tags), so callers can fold the discs into one title instead of
+/// treating each as a separate movie.  A bare trailing ``-1`` is handled
+/// per page by ``tag_disc_suffixes``.
+fn detect_disc_number(title: &str, tags: &[String]) -> Option<i32> {
+    std::iter::once(title)
+        .chain(tags.iter().map(String::as_str))
+        .find_map(|text| DISC_RE.captures(text))
+        .and_then(|c| c[1].parse::<i32>().ok())
+        .filter(|n| *n > 0)
+}
+
+/// Read a bare trailing ``-N`` on the title as a disc number only when
+/// another tile on the page shares the code; on its own, ``Vol-3`` or
+/// ``Part-2`` is part of the title.
+fn tag_disc_suffixes(movies: &mut [MovieIndexEntry]) {
+    let mut per_code: HashMap<String, usize> = HashMap::new();
+    for m in movies.iter().filter(|m| !m.video_code.is_empty()) {
+        *per_code.entry(normalize_video_code(&m.video_code)).or_insert(0) += 1;
+    }
+    for m in movies.iter_mut() {
+        if m.disc_number.is_some() || m.video_code.is_empty() {
+            continue;
+        }
+        if per_code.get(&normalize_video_code(&m.video_code)).copied().unwrap_or(0) < 2 {
+            continue;
+        }
+        m.disc_number = DISC_SUFFIX_RE
+            .captures(&m.title)
+            .and_then(|c| c[1].parse::<i32>().ok())
+            .filter(|n| *n > 0);
+    }
+}
 
 fn parse_movie_item(item: &ElementRef, page_num: i32) -> Option<MovieIndexEntry> {
     let a = item
@@ -79,6 +147,7 @@ fn parse_movie_item(item: &ElementRef, page_num: i32) -> Option<MovieIndexEntry>
         .select(&SEL_META)
         .next()
         .map_or(String::new(), |m| get_text_content(&m).trim().to_string());
+    let release_date = resolve_relative_date(&release_date, None).unwrap_or(release_date);
 
     // Tags
     let mut tags = Vec::new();
@@ -116,10 +185,13 @@ fn parse_movie_item(item: &ElementRef, page_num: i32) -> Option<MovieIndexEntry>
         }
     }
 
+    let disc_number = detect_disc_number(&title, &tags);
+
     Some(MovieIndexEntry {
         href,
         video_code,
         title,
+        rate_value: parse_rate_value(&rate),
         rate,
         comment_count,
         release_date,
@@ -127,10 +199,13 @@ fn parse_movie_item(item: &ElementRef, page_num: i32) -> Option<MovieIndexEntry>
         cover_url,
         page: page_num,
         ranking,
+        disc_number,
     })
 }
 
 pub fn parse_index_page(html_content: &str, page_num: i32) -> IndexPageResult {
+    let unwrapped = unwrap_saved_page(html_content);
+    let html_content: &str = &unwrapped;
     let document = Html::parse_document(html_content);
 
     let page_title = document
@@ -152,6 +227,9 @@ pub fn parse_index_page(html_content: &str, page_num: i32) -> IndexPageResult {
             has_movie_list: false,
             movies: Vec::new(),
             page_title,
+            next_page_href: None,
+            total_pages: None,
+            current_page: page_num,
         };
     }
 
@@ -163,16 +241,204 @@ pub fn parse_index_page(html_content: &str, page_num: i32) -> IndexPageResult {
             }
         }
     }
+    tag_disc_suffixes(&mut movies);
 
     debug!("[Page {}] Parsed {} movie entries", page_num, movies.len());
+    let (total_pages, current_page) = extract_pagination(&document);
     IndexPageResult {
         has_movie_list: true,
         movies,
         page_title,
+        next_page_href: extract_next_page_href(&document),
+        total_pages,
+        current_page: current_page.unwrap_or(page_num),
+    }
+}
+
+/// ``(total_pages, current_page)`` from the numbered ``nav.pagination``
+/// links: the highest number shown and the ``is-current`` one.  Both are
+/// ``None`` when the page has no pagination block.  An ellipsis after the
+/// highest link means more pages exist than are shown, so the total is
+/// unknown (``None``).
+fn extract_pagination(document: &Html) -> (Option<i32>, Option<i32>) {
+    let page_number = |a: &ElementRef| get_text_content(a).trim().parse::<i32>().ok();
+    let mut total = None;
+    let mut truncated = false;
+    for item in document.select(&SEL_PAGINATION_ITEM) {
+        if has_class(&item, "pagination-ellipsis") {
+            truncated = true;
+        } else if let Some(n) = page_number(&item) {
+            if !matches!(total, Some(max) if max >= n) {
+                total = Some(n);
+                truncated = false;
+            }
+        }
+    }
+    let total = if truncated { None } else { total };
+    let current = document
+        .select(&SEL_PAGINATION_CURRENT)
+        .next()
+        .and_then(|a| page_number(&a));
+    (total, current)
+}
+
+/// Href of the page after this one, read from ``nav.pagination``.
+///
+/// Prefers the ``a.pagination-next`` button (ignored when disabled or
+/// without a real href); otherwise takes the numbered link after the
+/// ``is-current`` one.  ``None`` means this is the last page.
+fn extract_next_page_href(document: &Html) -> Option<String> {
+    let usable = |a: ElementRef| {
+        if has_class(&a, "is-disabled") || a.value().attr("disabled").is_some() {
+            return None;
+        }
+        a.value()
+            .attr("href")
+            .map(str::trim)
+            .filter(|h| !h.is_empty() && *h != "#")
+            .map(str::to_string)
+    };
+
+    if let Some(next) = document.select(&SEL_PAGINATION_NEXT).next() {
+        return usable(next);
+    }
+
+    let current = document.select(&SEL_PAGINATION_CURRENT).next()?;
+    let item = current.parent().and_then(ElementRef::wrap)?;
+    let next_item = item.next_siblings().find_map(ElementRef::wrap)?;
+    let next_link = next_item.select(&SEL_A).next()?;
+    if has_class(&next_link, "pagination-ellipsis") {
+        return None;
+    }
+    usable(next_link)
+}
+
+/// Total title count from the section header (``共 1234 部影片`` / ``1234 movies``).
+/// Actor pages put an alias line in an earlier ``section-meta``, so every
+/// one is tried.
+pub(crate) fn extract_total_results(document: &Html) -> Option<i32> {
+    document.select(&SEL_SECTION_META).find_map(|meta| {
+        let text = get_text_content(&meta);
+        TOTAL_RESULTS_RE
+            .captures(&text)
+            .and_then(|c| c[1].replace(',', "").parse::<i32>().ok())
+    })
+}
+
+/// Description text and maker link from the header of a
+/// ``/video_codes/<prefix>`` page.  Paragraphs holding the maker link are
+/// not part of the description.
+fn extract_prefix_header(document: &Html) -> (Option<String>, Option<MovieLink>) {
+    let maker = document
+        .select(&SEL_SECTION_HEADER)
+        .find_map(|header| header.select(&SEL_MAKER_LINK).find_map(|a| extract_movie_link(&a)));
+    let description = document.select(&SEL_SECTION_DESCRIPTION).find_map(|block| {
+        let paragraphs: Vec<String> = block
+            .select(&SEL_PARAGRAPH)
+            .filter(|p| p.select(&SEL_MAKER_LINK).next().is_none())
+            .map(|p| get_text_content(&p).trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        let text = if paragraphs.is_empty() && block.select(&SEL_MAKER_LINK).next().is_none() {
+            get_text_content(&block).trim().to_string()
+        } else {
+            paragraphs.join("\n")
+        };
+        (!text.is_empty()).then_some(text)
+    });
+    (description, maker)
+}
+
+/// ``true`` when a ``section-meta`` line is a title count rather than text.
+pub(crate) fn is_total_results_text(text: &str) -> bool {
+    TOTAL_RESULTS_RE.is_match(text)
+}
+
+/// Drop entries whose tags hit ``exclude_tags`` or miss every ``include_tags``.
+///
+/// Both lists empty means no filtering.
+pub fn filter_entries_by_tags(
+    movies: Vec<MovieIndexEntry>,
+    include_tags: &[String],
+    exclude_tags: &[String],
+) -> Vec<MovieIndexEntry> {
+    if include_tags.is_empty() && exclude_tags.is_empty() {
+        return movies;
+    }
+    let before = movies.len();
+    let kept: Vec<MovieIndexEntry> = movies
+        .into_iter()
+        .filter(|m| !m.tags.iter().any(|t| exclude_tags.contains(t)))
+        .filter(|m| include_tags.is_empty() || m.tags.iter().any(|t| include_tags.contains(t)))
+        .collect();
+    if kept.len() < before {
+        debug!("Tag filter dropped {} of {} entries", before - kept.len(), before);
+    }
+    kept
+}
+
+/// Tag -> number of entries carrying it, across every movie in ``result``.
+pub fn collect_tags(result: &IndexPageResult) -> HashMap<String, i32> {
+    let mut counts: HashMap<String, i32> = HashMap::new();
+    for movie in &result.movies {
+        for tag in &movie.tags {
+            *counts.entry(tag.clone()).or_insert(0) += 1;
+        }
     }
+    counts
+}
+
+/// How much optional metadata an entry carries; used to pick among duplicates.
+fn metadata_score(entry: &MovieIndexEntry) -> usize {
+    [
+        !entry.title.is_empty(),
+        !entry.rate.is_empty(),
+        !entry.comment_count.is_empty(),
+        !entry.release_date.is_empty(),
+        !entry.cover_url.is_empty(),
+        !entry.tags.is_empty(),
+    ]
+    .iter()
+    .filter(|&&populated| populated)
+    .count()
+}
+
+/// Collapse entries sharing a ``video_code`` (compared via
+/// ``normalize_video_code``) and ``disc_number``, keeping the most populated
+/// one (earliest wins ties) at the position where the pair first appeared,
+/// so the discs of a multi-disc release stay separate.  Entries without a
+/// code are kept as they are.
+pub fn dedup_entries(entries: Vec<MovieIndexEntry>) -> Vec<MovieIndexEntry> {
+    let before = entries.len();
+    let mut kept: Vec<MovieIndexEntry> = Vec::with_capacity(before);
+    let mut position: HashMap<(String, Option<i32>), usize> = HashMap::new();
+    for entry in entries {
+        if entry.video_code.is_empty() {
+            kept.push(entry);
+            continue;
+        }
+        let key = (normalize_video_code(&entry.video_code), entry.disc_number);
+        match position.get(&key) {
+            Some(&i) => {
+                if metadata_score(&entry) > metadata_score(&kept[i]) {
+                    kept[i] = entry;
+                }
+            }
+            None => {
+                position.insert(key, kept.len());
+                kept.push(entry);
+            }
+        }
+    }
+    if kept.len() < before {
+        debug!("Dedup dropped {} of {} entries", before - kept.len(), before);
+    }
+    kept
 }
 
 pub fn parse_category_page(html_content: &str, page_num: i32) -> CategoryPageResult {
+    let unwrapped = unwrap_saved_page(html_content);
+    let html_content: &str = &unwrapped;
     let document = Html::parse_document(html_content);
     let base = parse_index_page(html_content, page_num);
 
@@ -185,16 +451,32 @@ pub fn parse_category_page(html_content: &str, page_num: i32) -> CategoryPageRes
         }
     }
 
+    // Series pages list episodes in release order; ``movies`` keeps the DOM
+    // order, and the header count doubles as the episode total.
+    let total_results = extract_total_results(&document);
+    let series_total = if cat_type == "series" { total_results } else { None };
+    let (prefix_description, prefix_maker) = if cat_type == "video_codes" {
+        extract_prefix_header(&document)
+    } else {
+        (None, None)
+    };
+
     CategoryPageResult {
         has_movie_list: base.has_movie_list,
         movies: base.movies,
         page_title: base.page_title,
         category_type: cat_type,
         category_name: cat_name,
+        total_results,
+        series_total,
+        prefix_description,
+        prefix_maker,
     }
 }
 
 pub fn parse_top_page(html_content: &str, page_num: i32) -> TopPageResult {
+    let unwrapped = unwrap_saved_page(html_content);
+    let html_content: &str = &unwrapped;
     let base = parse_index_page(html_content, page_num);
 
     let mut top_type = String::new();
@@ -231,3 +513,388 @@ pub fn parse_top_page(html_content: &str, page_num: i32) -> TopPageResult {
         period,
     }
 }
+
+/// An item is an actor tile when it links to ``/actors/...`` and has no
+/// ``video-title`` (movie tiles always carry one).
+fn is_actor_item(item: &ElementRef) -> bool {
+    item.select(&SEL_VIDEO_TITLE).next().is_none() && item.select(&SEL_ACTOR_LINK).next().is_some()
+}
+
+/// ``"female"`` / ``"male"`` from a ``strong.symbol`` class or a bare ♀ / ♂.
+fn actor_gender_hint(item: &ElementRef) -> Option<String> {
+    for strong in item.select(&SEL_STRONG) {
+        let class = strong.value().attr("class").unwrap_or("");
+        if class.contains("female") {
+            return Some("female".into());
+        }
+        if class.contains("male") {
+            return Some("male".into());
+        }
+    }
+    let text = get_text_content(item);
+    if text.contains('♀') {
+        Some("female".into())
+    } else if text.contains('♂') {
+        Some("male".into())
+    } else {
+        None
+    }
+}
+
+fn parse_actor_item(item: &ElementRef, page_num: i32) -> Option<ActorIndexEntry> {
+    let a = item.select(&SEL_ACTOR_LINK).next()?;
+    let href = a.value().attr("href").unwrap_or("").to_string();
+
+    let name = a
+        .select(&SEL_STRONG)
+        .map(|s| get_text_content(&s).trim().to_string())
+        .find(|t| !t.is_empty() && !t.contains(['♀', '♂']))
+        .or_else(|| a.value().attr("title").map(|t| t.trim().to_string()))
+        .unwrap_or_else(|| get_text_content(&a).trim().to_string());
+    if name.is_empty() {
+        return None;
+    }
+
+    let avatar_url = item
+        .select(&SEL_IMG)
+        .next()
+        .and_then(|img| {
+            let attr = |name| img.value().attr(name).filter(|v: &&str| !v.is_empty());
+            attr("src").or_else(|| attr("data-src"))
+        })
+        .map(String::from);
+
+    Some(ActorIndexEntry {
+        name,
+        href,
+        avatar_url,
+        gender: actor_gender_hint(item),
+        page: page_num,
+    })
+}
+
+/// Parse an actor listing page (``/actors``, or actor tiles mixed into an
+/// index page).  Movie tiles are skipped.
+pub fn parse_actor_page(html_content: &str, page_num: i32) -> ActorPageResult {
+    let unwrapped = unwrap_saved_page(html_content);
+    let document = Html::parse_document(&unwrapped);
+
+    let page_title = document
+        .select(&SEL_TITLE)
+        .next()
+        .map_or(String::new(), |t| get_text_content(&t).trim().to_string());
+
+    let actors: Vec<ActorIndexEntry> = document
+        .select(&SEL_ACTOR_ITEM)
+        .filter(is_actor_item)
+        .filter_map(|item| parse_actor_item(&item, page_num))
+        .collect();
+    if actors.is_empty() {
+        warn!("[Page {}] No actor list found", page_num);
+    } else {
+        debug!("[Page {}] Parsed {} actors", page_num, actors.len());
+    }
+
+    ActorPageResult {
+        has_actor_list: !actors.is_empty(),
+        actors,
+        page_title,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_actor_page() {
+        let html = include_str!("../../tests/fixtures/parser/actors_listing.html");
+        let result = parse_actor_page(html, 2);
+        assert!(result.has_actor_list);
+        assert_eq!(result.actors.len(), 3);
+
+        let first = &result.actors[0];
+        assert_eq!(first.name, "Actress One");
+        assert_eq!(first.href, "/actors/AbC1");
+        assert_eq!(
+            first.avatar_url.as_deref(),
+            Some("https://c0.jdbstatic.com/avatars/ab/AbC1.jpg")
+        );
+        assert_eq!(first.gender.as_deref(), Some("female"));
+        assert_eq!(first.page, 2);
+
+        assert_eq!(result.actors[1].gender.as_deref(), Some("male"));
+        assert_eq!(
+            result.actors[1].avatar_url.as_deref(),
+            Some("https://c0.jdbstatic.com/avatars/de/DeF2.jpg")
+        );
+        assert_eq!(result.actors[2].name, "No Avatar");
+        assert_eq!(result.actors[2].avatar_url, None);
+        assert_eq!(result.actors[2].gender, None);
+
+        assert!(!parse_actor_page("<html><body></body></html>", 1).has_actor_list);
+    }
+
+    fn index_html(items: &[(&str, &str, &str)]) -> String {
+        let body: String = items
+            .iter()
+            .map(|(href, code, title)| {
+                format!(
+                    r#"<div class="item"><a class="box" href="{href}">
+                        <div class="video-title"><strong>{code}</strong> {title}</div>
+                    </a></div>"#
+                )
+            })
+            .collect();
+        format!(r#"<html><body><div class="movie-list">{body}</div></body></html>"#)
+    }
+
+    /// One index tile for ``href`` carrying ``tags``.
+    fn tagged_tile(href: &str, tags: &[&str]) -> String {
+        let spans: String = tags
+            .iter()
+            .map(|t| format!(r#"<span class="tag">{t}</span>"#))
+            .collect();
+        format!(
+            r#"<div class="item"><a class="box" href="{href}">
+                <div class="video-title"><strong>ABC-1</strong> T</div>
+                <div class="tags has-addons">{spans}</div>
+            </a></div>"#
+        )
+    }
+
+    #[test]
+    fn test_parse_category_page_video_codes_total() {
+        let items = index_html(&[("/v/a", "STAR-001", "First"), ("/v/b", "STAR-002", "Second")]);
+        let html = items.replace(
+            "<html><body>",
+            r#"<html><head>
+                <title>STAR | JavDB</title>
+                <link rel="canonical" href="https://javdb.com/video_codes/STAR">
+              </head><body>
+              <h2 class="section-title">
+                <span class="section-name">STAR</span>
+                <span class="section-meta">共 1,234 部影片</span>
+              </h2>"#,
+        );
+        let result = parse_category_page(&html, 1);
+        assert_eq!(result.category_type, "video_codes");
+        assert_eq!(result.category_name, "STAR");
+        assert_eq!(result.total_results, Some(1234));
+        assert_eq!(result.movies.len(), 2);
+
+        let plain = parse_category_page(&items, 1);
+        assert_eq!(plain.total_results, None);
+        assert_eq!(result.series_total, None);
+    }
+
+    #[test]
+    fn test_series_order_preserved() {
+        let html = include_str!("../../tests/fixtures/parser/category_series.html");
+        let result = parse_category_page(html, 1);
+        assert_eq!(result.category_type, "series");
+        assert_eq!(result.category_name, "Edge Series");
+        assert_eq!(result.series_total, Some(4));
+        let codes: Vec<&str> = result.movies.iter().map(|m| m.video_code.as_str()).collect();
+        assert_eq!(codes, ["SER-003", "SER-001", "SER-004", "SER-002"]);
+    }
+
+    #[test]
+    fn test_video_codes_prefix_header() {
+        let html = include_str!("../../tests/fixtures/parser/category_video_codes_prefix.html");
+        let result = parse_category_page(html, 1);
+        assert_eq!(result.category_type, "video_codes");
+        assert_eq!(result.category_name, "STAR");
+        assert_eq!(
+            result.prefix_description.as_deref(),
+            Some("STAR is the flagship label of the maker.\nReleases monthly.")
+        );
+        let maker = result.prefix_maker.unwrap();
+        assert_eq!(maker.name, "Prefix Maker");
+        assert_eq!(maker.href, "/makers/Pm1");
+        assert_eq!(result.movies.len(), 1);
+
+        let series = parse_category_page(
+            include_str!("../../tests/fixtures/parser/category_series.html"),
+            1,
+        );
+        assert_eq!(series.prefix_description, None);
+        assert!(series.prefix_maker.is_none());
+    }
+
+    #[test]
+    fn test_next_page_href_from_pagination() {
+        let items = index_html(&[("/v/a", "ABC-001", "First")]);
+        let with_next = items.replace(
+            "</body>",
+            r#"<nav class="pagination">
+                <a class="pagination-previous" href="/actors/x?page=1&amp;t=d">上一頁</a>
+                <a class="pagination-next" href="/actors/x?page=3&amp;t=d">下一頁</a>
+                <ul class="pagination-list">
+                  <li><a class="pagination-link" href="/actors/x?page=1&amp;t=d">1</a></li>
+                  <li><a class="pagination-link is-current" href="/actors/x?page=2&amp;t=d">2</a></li>
+                  <li><a class="pagination-link" href="/actors/x?page=3&amp;t=d">3</a></li>
+                </ul>
+              </nav></body>"#,
+        );
+        let result = parse_index_page(&with_next, 2);
+        assert_eq!(result.next_page_href.as_deref(), Some("/actors/x?page=3&t=d"));
+
+        let numbered_only = items.replace(
+            "</body>",
+            r#"<nav class="pagination"><ul class="pagination-list">
+                  <li><a class="pagination-link is-current" href="/?page=1">1</a></li>
+                  <li><a class="pagination-link" href="/?page=2">2</a></li>
+              </ul></nav></body>"#,
+        );
+        let result = parse_index_page(&numbered_only, 1);
+        assert_eq!(result.next_page_href.as_deref(), Some("/?page=2"));
+
+        let last_page = items.replace(
+            "</body>",
+            r#"<nav class="pagination">
+                <a class="pagination-previous" href="/?page=2">上一頁</a>
+                <ul class="pagination-list">
+                  <li><a class="pagination-link" href="/?page=2">2</a></li>
+                  <li><a class="pagination-link is-current" href="/?page=3">3</a></li>
+                </ul>
+              </nav></body>"#,
+        );
+        assert_eq!(parse_index_page(&last_page, 3).next_page_href, None);
+        assert_eq!(parse_index_page(&items, 1).next_page_href, None);
+    }
+
+    #[test]
+    fn test_total_and_current_page_from_pagination() {
+        let items = index_html(&[("/v/a", "ABC-001", "First")]);
+        let links: String = (1..=9)
+            .map(|n| {
+                let current = if n == 4 { " is-current" } else { "" };
+                format!(r#"<li><a class="pagination-link{current}" href="/?page={n}">{n}</a></li>"#)
+            })
+            .collect();
+        let paged = items.replace(
+            "</body>",
+            &format!(
+                r#"<nav class="pagination"><ul class="pagination-list">{links}
+                  <li><span class="pagination-ellipsis">&hellip;</span></li>
+                </ul></nav></body>"#
+            ),
+        );
+        // More pages follow the ellipsis: the total is unknown.
+        let result = parse_index_page(&paged, 4);
+        assert_eq!(result.total_pages, None);
+        assert_eq!(result.current_page, 4);
+
+        let with_last = paged.replace(
+            "</li>\n                </ul>",
+            r#"</li><li><a class="pagination-link" href="/?page=40">40</a></li></ul>"#,
+        );
+        assert_eq!(parse_index_page(&with_last, 4).total_pages, Some(40));
+        let complete = paged.replace("pagination-ellipsis", "is-hidden");
+        assert_eq!(parse_index_page(&complete, 4).total_pages, Some(9));
+
+        let plain = parse_index_page(&items, 2);
+        assert_eq!(plain.total_pages, None);
+        assert_eq!(plain.current_page, 2);
+    }
+
+    #[test]
+    fn test_collect_tags_counts_occurrences() {
+        let html = format!(
+            r#"<html><body><div class="movie-list">{}{}{}</div></body></html>"#,
+            tagged_tile("/v/a", &["含中字磁鏈", "今日新種"]),
+            tagged_tile("/v/b", &["含磁鏈", "今日新種"]),
+            tagged_tile("/v/c", &["含中字磁鏈", "今日新種"]),
+        );
+        let tags = collect_tags(&parse_index_page(&html, 1));
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags["今日新種"], 3);
+        assert_eq!(tags["含中字磁鏈"], 2);
+        assert_eq!(tags["含磁鏈"], 1);
+
+        assert!(collect_tags(&IndexPageResult::default()).is_empty());
+    }
+
+    #[test]
+    fn test_dedup_entries_keeps_most_complete() {
+        let html = r#"<html><body><div class="movie-list">
+            <div class="item"><a class="box" href="/v/a1">
+              <div class="video-title"><strong>ABC-001</strong> First</div></a></div>
+            <div class="item"><a class="box" href="/v/b">
+              <div class="video-title"><strong>XYZ-002</strong> Other</div></a></div>
+            <div class="item"><a class="box" href="/v/a2">
+              <div class="video-title"><strong>ABC-001</strong> First</div>
+              <div class="score"><span class="value">4.5分, 由10人評價</span></div>
+              <div class="tags has-addons"><span class="tag">含中字磁鏈</span></div></a></div>
+        </div></body></html>"#;
+        let movies = parse_index_page(html, 1).movies;
+        assert_eq!(movies.len(), 3);
+
+        let deduped = dedup_entries(movies);
+        let hrefs: Vec<&str> = deduped.iter().map(|m| m.href.as_str()).collect();
+        assert_eq!(hrefs, vec!["/v/a2", "/v/b"]);
+        assert_eq!(deduped[0].rate, "4.5");
+        assert_eq!(deduped[0].rate_value, Some(4.5));
+        assert_eq!(deduped[1].rate_value, None);
+        assert_eq!(deduped[0].tags, vec!["含中字磁鏈"]);
+
+        // Two discs of one release share the code but are not duplicates.
+        let html = index_html(&[
+            ("/v/cd1", "ABC-123", "Long Release CD1"),
+            ("/v/cd2", "ABC-123", "Long Release CD2"),
+            ("/v/cd1-again", "ABC-123", "Long Release CD1"),
+        ]);
+        let deduped = dedup_entries(parse_index_page(&html, 1).movies);
+        let hrefs: Vec<&str> = deduped.iter().map(|m| m.href.as_str()).collect();
+        assert_eq!(hrefs, vec!["/v/cd1", "/v/cd2"]);
+    }
+
+    #[test]
+    fn test_filter_entries_by_tags() {
+        let html = format!(
+            r#"<html><body><div class="movie-list">{}{}{}</div></body></html>"#,
+            tagged_tile("/v/a", &["含中字磁鏈", "今日新種"]),
+            tagged_tile("/v/b", &["含磁鏈"]),
+            tagged_tile("/v/c", &["VR"]),
+        );
+        let movies = parse_index_page(&html, 1).movies;
+        let hrefs = |ms: &[MovieIndexEntry]| ms.iter().map(|m| m.href.clone()).collect::<Vec<_>>();
+
+        let all = filter_entries_by_tags(movies.clone(), &[], &[]);
+        assert_eq!(hrefs(&all), vec!["/v/a", "/v/b", "/v/c"]);
+
+        let no_vr = filter_entries_by_tags(movies.clone(), &[], &["VR".to_string()]);
+        assert_eq!(hrefs(&no_vr), vec!["/v/a", "/v/b"]);
+
+        let only = filter_entries_by_tags(
+            movies,
+            &["含中字磁鏈".to_string(), "含磁鏈".to_string()],
+            &["今日新種".to_string()],
+        );
+        assert_eq!(hrefs(&only), vec!["/v/b"]);
+    }
+
+    #[test]
+    fn test_detect_disc_number() {
+        let html = index_html(&[
+            ("/v/a", "ABC-123", "Long Release CD1"),
+            ("/v/b", "ABC-123", "Long Release CD2"),
+            ("/v/c", "ABC-124", "Another Release-1"),
+            ("/v/d", "ABC-125", "Single Disc Title"),
+            ("/v/e", "ABC-124", "Another Release-2"),
+        ]);
+        let result = parse_index_page(&html, 1);
+        let discs: Vec<Option<i32>> = result.movies.iter().map(|m| m.disc_number).collect();
+        assert_eq!(discs, vec![Some(1), Some(2), Some(1), None, Some(2)]);
+
+        // A trailing -N on a code seen once is part of the title.
+        let html = index_html(&[
+            ("/v/f", "ABC-126", "Series Vol-3"),
+            ("/v/g", "ABC-127", "Story Part-2"),
+            ("/v/h", "ABC-128", "Drama Episode-1"),
+        ]);
+        let result = parse_index_page(&html, 1);
+        assert!(result.movies.iter().all(|m| m.disc_number.is_none()));
+    }
+}
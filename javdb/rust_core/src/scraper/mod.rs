@@ -1,3 +1,4 @@
+pub mod actor_parser;
 pub mod common;
 pub mod detail_parser;
 pub mod index_parser;
@@ -5,7 +5,7 @@ use scraper::{ElementRef, Html, Selector};
 use std::collections::{HashMap, HashSet};
 
 use crate::models::{TagCategory, TagOption, TagPageResult};
-use crate::scraper::common::{get_text_content, has_class};
+use crate::scraper::common::{get_text_content, has_class, unwrap_saved_page};
 use crate::scraper::index_parser::parse_index_page;
 
 static SEL_TAGS_DIV: Lazy<Selector> = Lazy::new(|| Selector::parse("div#tags").unwrap());
@@ -22,6 +22,19 @@ static CANONICAL_RE: Lazy<Regex> = Lazy::new(|| {
 static CATEGORY_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"tag-category-(\d+)").unwrap());
 static CPARAM_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^c(\d+)$").unwrap());
+static OPTION_COUNT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.*?)\s*[(（]\s*(\d[\d,]*)\s*[)）]$").unwrap());
+
+/// Split a trailing title count off an option label: ``"巨乳 (1,234)"`` ->
+/// ``("巨乳", Some(1234))``.  Labels without a count are returned unchanged.
+fn split_option_count(text: &str) -> (String, Option<i32>) {
+    if let Some(caps) = OPTION_COUNT_RE.captures(text) {
+        if let Ok(count) = caps[2].replace(',', "").parse() {
+            return (caps[1].to_string(), Some(count));
+        }
+    }
+    (text.to_string(), None)
+}
 
 fn extract_page_url(html_content: &str) -> String {
     let prefix = if html_content.len() > 3000 {
@@ -118,6 +131,8 @@ fn extract_new_tag_id_from_href(
 }
 
 pub fn parse_tag_page(html_content: &str, page_num: i32) -> TagPageResult {
+    let unwrapped = unwrap_saved_page(html_content);
+    let html_content: &str = &unwrapped;
     let document = Html::parse_document(html_content);
     let index_result = parse_index_page(html_content, page_num);
 
@@ -213,6 +228,7 @@ pub fn parse_tag_page(html_content: &str, page_num: i32) -> TagPageResult {
                 if tag_name.is_empty() {
                     continue;
                 }
+                let (tag_name, count) = split_option_count(&tag_name);
 
                 let tag_id = if !cat_current.is_empty() {
                     "__selected__".to_string()
@@ -224,6 +240,7 @@ pub fn parse_tag_page(html_content: &str, page_num: i32) -> TagPageResult {
                     name: tag_name,
                     tag_id,
                     selected: true,
+                    count,
                 });
             }
             // Non-selected tag: <a class="tag ...">
@@ -232,6 +249,7 @@ pub fn parse_tag_page(html_content: &str, page_num: i32) -> TagPageResult {
                 if tag_name.is_empty() {
                     continue;
                 }
+                let (tag_name, count) = split_option_count(&tag_name);
 
                 let href = child.value().attr("href").unwrap_or("");
                 let tag_id = if !href.is_empty() && !href.contains("javascript") {
@@ -248,6 +266,7 @@ pub fn parse_tag_page(html_content: &str, page_num: i32) -> TagPageResult {
                     name: tag_name,
                     tag_id,
                     selected: false,
+                    count,
                 });
             }
         }
@@ -312,3 +331,51 @@ pub fn parse_tag_page(html_content: &str, page_num: i32) -> TagPageResult {
         current_selections,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_selected_pairs_expands_multi_values() {
+        let html = r#"<html><head>
+            <link rel="canonical" href="https://javdb.com/tags?c1=5,7&c2=3">
+            </head><body><div class="movie-list"></div></body></html>"#;
+        let result = parse_tag_page(html, 1);
+        assert_eq!(result.current_selections["1"], "5,7");
+        assert_eq!(
+            result.get_selected_pairs(),
+            vec![
+                ("1".to_string(), "5".to_string()),
+                ("1".to_string(), "7".to_string()),
+                ("2".to_string(), "3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tag_option_counts() {
+        let html = r#"<html><head>
+            <link rel="canonical" href="https://javdb.com/tags?c1=5">
+            </head><body><div id="tags"><dl>
+              <dt class="tag-category" data-cid="1"><strong>主題</strong>
+                <span class="tag_labels">
+                  <div class="tag is-info">巨乳 (1,234)<button class="delete"></button></div>
+                  <a class="tag" href="/tags?c1=5,9">美少女 (87)</a>
+                  <a class="tag" href="/tags?c1=5,12">單體作品</a>
+                </span>
+              </dt>
+            </dl></div><div class="movie-list"></div></body></html>"#;
+        let result = parse_tag_page(html, 1);
+        let options = &result.categories[0].options;
+        assert_eq!(options.len(), 3);
+        assert_eq!(options[0].name, "巨乳");
+        assert_eq!(options[0].count, Some(1234));
+        assert!(options[0].selected);
+        assert_eq!(options[1].name, "美少女");
+        assert_eq!(options[1].count, Some(87));
+        assert_eq!(options[1].tag_id, "9");
+        assert_eq!(options[2].name, "單體作品");
+        assert_eq!(options[2].count, None);
+    }
+}
@@ -3,10 +3,19 @@ use regex::Regex;
 use std::sync::LazyLock;
 use url::Url;
 
+use crate::models::{MovieDetail, MovieLink};
+use crate::scraper::common::normalize_video_code;
+
 static WHITESPACE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
 static NON_SAFE_CHAR_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"[^\w\u4e00-\u9fff\u3040-\u309f\u30a0-\u30ff\-]").unwrap());
 static MULTI_UNDERSCORE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"_+").unwrap());
+static PLACEHOLDER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{(\w+)\}").unwrap());
+static EMPTY_BRACKETS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\s*\]|\(\s*\)").unwrap());
+static YEAR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(\d{4})\b").unwrap());
+static MULTI_SEPARATOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[_\-]*_[_\-]*").unwrap());
 
 fn path_prefix(url_str: &str) -> Option<String> {
     let parsed = Url::parse(url_str).ok()?;
@@ -201,6 +210,87 @@ pub fn sanitize_filename_part(text: &str, max_length: usize) -> String {
     sanitized
 }
 
+/// Fill a filename ``template`` such as ``"{video_code} {title} [{actor}]"``
+/// from a parsed detail page.
+///
+/// Placeholders: ``video_code``, ``title``, ``actor`` (first credited actor),
+/// ``actors`` (all, comma-separated), ``release_date``, ``maker``,
+/// ``publisher`` and ``series``; unknown ones are left as written.  The result
+/// has filesystem-unsafe characters replaced by spaces, brackets left empty by
+/// missing values dropped, whitespace collapsed, and is cut to ``max_length``
+/// bytes at a character boundary without a trailing space or dot.
+#[pyfunction]
+#[pyo3(signature = (detail, template, max_length=200))]
+pub fn build_movie_filename(detail: &MovieDetail, template: &str, max_length: usize) -> String {
+    let link_name = |link: &Option<MovieLink>| {
+        link.as_ref().map_or(String::new(), |l| l.name.clone())
+    };
+    let filled = PLACEHOLDER_RE.replace_all(template, |caps: &regex::Captures| {
+        match &caps[1] {
+            "video_code" => detail.video_code.clone(),
+            "title" => detail.title.clone(),
+            "actor" => detail.actors.first().map_or(String::new(), |a| a.name.clone()),
+            "actors" => detail
+                .actors
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+            "release_date" => detail.release_date.clone(),
+            "maker" => link_name(&detail.maker),
+            "publisher" => link_name(&detail.publisher),
+            "series" => link_name(&detail.series),
+            _ => caps[0].to_string(),
+        }
+    });
+
+    let unsafe_chars = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+    let cleaned: String = filled
+        .chars()
+        .map(|c| if unsafe_chars.contains(&c) || c.is_control() { ' ' } else { c })
+        .collect();
+    let cleaned = EMPTY_BRACKETS_RE.replace_all(&cleaned, "");
+    let mut cleaned = WHITESPACE_RE.replace_all(cleaned.trim(), " ").to_string();
+
+    if cleaned.len() > max_length {
+        let mut end = max_length;
+        while end > 0 && !cleaned.is_char_boundary(end) {
+            end -= 1;
+        }
+        cleaned.truncate(end);
+    }
+    cleaned.trim_end_matches([' ', '.']).to_string()
+}
+
+/// Canonical dedup key for a parsed detail page, filled from ``template``
+/// (default ``"{video_code}"``; use ``"{video_code}_{year}"`` where one code
+/// is reused across years).
+///
+/// Placeholders: ``video_code`` (normalized, see ``normalize_video_code``),
+/// ``year`` (from ``release_date``) and ``actor`` (first credited actor);
+/// unknown ones expand to nothing.  Whitespace becomes ``_``, other
+/// characters outside ``[\w-]`` and CJK are dropped, and separators left
+/// dangling by missing values are collapsed, so ``STAR-123_`` is ``STAR-123``.
+#[pyfunction]
+#[pyo3(signature = (detail, template="{video_code}"))]
+pub fn build_dedup_key(detail: &MovieDetail, template: &str) -> String {
+    let filled = PLACEHOLDER_RE.replace_all(template, |caps: &regex::Captures| {
+        match &caps[1] {
+            "video_code" => normalize_video_code(&detail.video_code),
+            "year" => YEAR_RE
+                .captures(&detail.release_date)
+                .map_or(String::new(), |c| c[1].to_string()),
+            "actor" => detail.actors.first().map_or(String::new(), |a| a.name.clone()),
+            _ => String::new(),
+        }
+    });
+
+    let key = WHITESPACE_RE.replace_all(filled.trim(), "_");
+    let key = NON_SAFE_CHAR_RE.replace_all(&key, "");
+    let key = MULTI_SEPARATOR_RE.replace_all(&key, "_");
+    key.trim_matches(['_', '-']).to_string()
+}
+
 #[pyfunction]
 pub fn extract_url_part_after_javdb(url: &str) -> String {
     const FALLBACK: &str = "custom_url";
@@ -227,3 +317,91 @@ pub fn extract_url_part_after_javdb(url: &str) -> String {
 
     if result.is_empty() { FALLBACK.into() } else { result }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ActorCredit;
+
+    fn sample_detail() -> MovieDetail {
+        MovieDetail {
+            video_code: "ABC-123".to_string(),
+            title: "Title: A/B <Special>?".to_string(),
+            release_date: "2024-05-01".to_string(),
+            maker: Some(MovieLink {
+                name: "S1".to_string(),
+                href: "/makers/x".to_string(),
+                work_count: None,
+            }),
+            actors: vec![
+                ActorCredit {
+                    name: "Actor One".to_string(),
+                    href: "/actors/1".to_string(),
+                    gender: "female".to_string(),
+                    role: None,
+                },
+                ActorCredit {
+                    name: "Actor Two".to_string(),
+                    href: "/actors/2".to_string(),
+                    gender: "female".to_string(),
+                    role: None,
+                },
+            ],
+            ..MovieDetail::default()
+        }
+    }
+
+    #[test]
+    fn test_build_movie_filename_expands_and_sanitizes() {
+        let detail = sample_detail();
+        assert_eq!(
+            build_movie_filename(&detail, "{video_code} {title} [{actor}]", 200),
+            "ABC-123 Title A B Special [Actor One]"
+        );
+        assert_eq!(
+            build_movie_filename(&detail, "{maker}/{release_date} {actors} {unknown}", 200),
+            "S1 2024-05-01 Actor One,Actor Two {unknown}"
+        );
+        assert_eq!(
+            build_movie_filename(&detail, "{video_code} ({series}) {title}", 200),
+            "ABC-123 Title A B Special"
+        );
+    }
+
+    #[test]
+    fn test_build_dedup_key_templates() {
+        let mut detail = sample_detail();
+        detail.video_code = "abc00123".to_string();
+        assert_eq!(build_dedup_key(&detail, "{video_code}"), "ABC-123");
+        assert_eq!(build_dedup_key(&detail, "{video_code}_{year}"), "ABC-123_2024");
+        assert_eq!(
+            build_dedup_key(&detail, "{video_code}_{actor}_{year}"),
+            "ABC-123_Actor_One_2024"
+        );
+
+        detail.release_date.clear();
+        detail.actors.clear();
+        assert_eq!(build_dedup_key(&detail, "{video_code}_{year}"), "ABC-123");
+        assert_eq!(build_dedup_key(&detail, "{video_code}_{actor}_{year}"), "ABC-123");
+        assert_eq!(build_dedup_key(&detail, "{video_code}_{unknown}"), "ABC-123");
+    }
+
+    #[test]
+    fn test_build_dedup_key_sanitizes() {
+        let mut detail = sample_detail();
+        detail.video_code = " star-123 ".to_string();
+        detail.actors[0].name = "名前 / Name?".to_string();
+        assert_eq!(build_dedup_key(&detail, "{video_code}_{actor}"), "STAR-123_名前_Name");
+        assert_eq!(build_dedup_key(&detail, "{video_code}: {year}"), "STAR-123_2024");
+    }
+
+    #[test]
+    fn test_build_movie_filename_truncates_on_char_boundary() {
+        let mut detail = sample_detail();
+        detail.title = "長いタイトル. 続き".to_string();
+        // Each kana/kanji is three bytes: 7 bytes hold two of them.
+        assert_eq!(build_movie_filename(&detail, "{title}", 7), "長い");
+        assert_eq!(build_movie_filename(&detail, "{title}", 19), "長いタイトル");
+        assert_eq!(build_movie_filename(&detail, "{video_code}", 3), "ABC");
+    }
+}
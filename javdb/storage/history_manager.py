@@ -22,12 +22,18 @@ from javdb.pipeline.policies import (
     determine_torrent_types as _ingestion_determine_torrent_types,
     get_missing_torrent_types as _ingestion_get_missing_torrent_types,
     has_complete_subtitles as _ingestion_has_complete_subtitles,
+    history_now as _history_now,
     should_process_movie as _ingestion_should_process_movie,
     should_skip_recent_today_release as _ingestion_should_skip_recent_today_release,
     should_skip_recent_yesterday_release as _ingestion_should_skip_recent_yesterday_release,
 )
 from javdb.storage.repos.history_repo import HistoryRepo
 
+try:
+    from javdb.rust_core import video_codes_equal as _rust_video_codes_equal
+except ImportError:
+    _rust_video_codes_equal = None
+
 logger = get_logger(__name__)
 
 RUST_HISTORY_AVAILABLE = False
@@ -497,6 +503,15 @@ def _csv_maintain_history_limit(history_file, max_records=1000):
         logger.error(f"Error maintaining history limit: {e}")
 
 
+def _video_codes_equal(a, b):
+    """Same-title check of the Rust history writer.  Without the Rust core
+    only case and surrounding whitespace are ignored."""
+    if _rust_video_codes_equal is not None:
+        return _rust_video_codes_equal(a or '', b or '')
+    a = (a or '').strip().upper()
+    return bool(a) and a == (b or '').strip().upper()
+
+
 def _csv_save_parsed_movie_to_history(history_file, href, phase, video_code, magnet_links=None, size_links=None):
     if magnet_links is None:
         magnet_links = {'no_subtitle': ''}
@@ -508,17 +523,24 @@ def _csv_save_parsed_movie_to_history(history_file, href, phase, video_code, mag
     records = []
     file_exists = os.path.exists(history_file)
     existing_count = 0
-    current_time = datetime.now().strftime("%Y-%m-%d %H:%M:%S")
-    current_date = datetime.now().strftime("%Y-%m-%d")
+    now = _history_now()
+    current_time = now.strftime("%Y-%m-%d %H:%M:%S")
+    current_date = now.strftime("%Y-%m-%d")
     updated_record = None
 
     if file_exists:
         try:
             with open(history_file, 'r', encoding='utf-8-sig') as f:
-                reader = csv.DictReader(f)
-                for row in reader:
-                    if row['href'] == href:
+                rows = list(csv.DictReader(f))
+                # Rows under the same title with another href only stand in
+                # when the href itself has no row; the match is saved under
+                # ``href``.
+                by_href = any(row['href'] == href for row in rows)
+                for row in rows:
+                    if (row['href'] == href if by_href
+                            else _video_codes_equal(row.get('video_code'), video_code)):
                         existing_count += 1
+                        row['href'] = href
 
                         if 'torrent_type' in row:
                             existing_torrent_types = row.get('torrent_type', '').split(',')
@@ -651,7 +673,7 @@ def _csv_validate_history_file(history_file):
 def _csv_batch_update_last_visited(history_file, visited_hrefs):
     if not visited_hrefs or not os.path.exists(history_file):
         return
-    current_time = datetime.now().strftime("%Y-%m-%d %H:%M:%S")
+    current_time = _history_now().strftime("%Y-%m-%d %H:%M:%S")
     visited_set = set(visited_hrefs)
     try:
         with open(history_file, 'r', encoding='utf-8-sig') as f:
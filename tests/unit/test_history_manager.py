@@ -254,8 +254,8 @@ class TestShouldSkipRecentYesterdayRelease:
 
     def test_yesterday_release_recently_visited_should_skip(self):
         """Yesterday release + visited today → skip."""
-        from datetime import datetime
-        today = datetime.now().strftime('%Y-%m-%d %H:%M:%S')
+        from javdb.pipeline.policies import history_now
+        today = history_now().strftime('%Y-%m-%d %H:%M:%S')
         history_data = {
             '/v/ABC-123': {'last_visited_datetime': today, 'update_datetime': today, 'torrent_types': ['subtitle']}
         }
@@ -263,8 +263,9 @@ class TestShouldSkipRecentYesterdayRelease:
 
     def test_yesterday_release_visited_yesterday_should_skip(self):
         """Yesterday release + visited yesterday → skip."""
-        from datetime import datetime, timedelta
-        yesterday = (datetime.now() - timedelta(days=1)).strftime('%Y-%m-%d %H:%M:%S')
+        from datetime import timedelta
+        from javdb.pipeline.policies import history_now
+        yesterday = (history_now() - timedelta(days=1)).strftime('%Y-%m-%d %H:%M:%S')
         history_data = {
             '/v/ABC-123': {'last_visited_datetime': yesterday, 'update_datetime': yesterday, 'torrent_types': ['no_subtitle']}
         }
@@ -272,8 +273,9 @@ class TestShouldSkipRecentYesterdayRelease:
 
     def test_yesterday_release_old_visit_should_not_skip(self):
         """Yesterday release + visited 3 days ago → do not skip."""
-        from datetime import datetime, timedelta
-        old_date = (datetime.now() - timedelta(days=3)).strftime('%Y-%m-%d %H:%M:%S')
+        from datetime import timedelta
+        from javdb.pipeline.policies import history_now
+        old_date = (history_now() - timedelta(days=3)).strftime('%Y-%m-%d %H:%M:%S')
         history_data = {
             '/v/ABC-123': {'last_visited_datetime': old_date, 'update_datetime': old_date, 'torrent_types': ['subtitle']}
         }
@@ -281,8 +283,8 @@ class TestShouldSkipRecentYesterdayRelease:
 
     def test_today_release_recently_visited_should_not_skip(self):
         """Today release (is_yesterday_release=False) + recent visit → do not skip."""
-        from datetime import datetime
-        today = datetime.now().strftime('%Y-%m-%d %H:%M:%S')
+        from javdb.pipeline.policies import history_now
+        today = history_now().strftime('%Y-%m-%d %H:%M:%S')
         history_data = {
             '/v/ABC-123': {'last_visited_datetime': today, 'update_datetime': today, 'torrent_types': ['subtitle']}
         }
@@ -294,8 +296,8 @@ class TestShouldSkipRecentTodayRelease:
 
     def test_today_release_visited_today_should_skip(self):
         """Today release + visited today -> skip."""
-        from datetime import datetime
-        today = datetime.now().strftime('%Y-%m-%d %H:%M:%S')
+        from javdb.pipeline.policies import history_now
+        today = history_now().strftime('%Y-%m-%d %H:%M:%S')
         history_data = {
             '/v/ABC-123': {'last_visited_datetime': today, 'update_datetime': today, 'torrent_types': ['subtitle']}
         }
@@ -303,8 +305,9 @@ class TestShouldSkipRecentTodayRelease:
 
     def test_today_release_visited_yesterday_should_not_skip(self):
         """Today release + visited yesterday -> do not skip."""
-        from datetime import datetime, timedelta
-        yesterday = (datetime.now() - timedelta(days=1)).strftime('%Y-%m-%d %H:%M:%S')
+        from datetime import timedelta
+        from javdb.pipeline.policies import history_now
+        yesterday = (history_now() - timedelta(days=1)).strftime('%Y-%m-%d %H:%M:%S')
         history_data = {
             '/v/ABC-123': {'last_visited_datetime': yesterday, 'update_datetime': yesterday, 'torrent_types': ['subtitle']}
         }
@@ -312,8 +315,8 @@ class TestShouldSkipRecentTodayRelease:
 
     def test_yesterday_release_flag_should_not_skip(self):
         """is_today_release=False + recent visit -> do not skip."""
-        from datetime import datetime
-        today = datetime.now().strftime('%Y-%m-%d %H:%M:%S')
+        from javdb.pipeline.policies import history_now
+        today = history_now().strftime('%Y-%m-%d %H:%M:%S')
         history_data = {
             '/v/ABC-123': {'last_visited_datetime': today, 'update_datetime': today, 'torrent_types': ['subtitle']}
         }
@@ -321,8 +324,9 @@ class TestShouldSkipRecentTodayRelease:
 
     def test_today_release_visited_old_should_not_skip(self):
         """Today release + visited 3 days ago -> do not skip."""
-        from datetime import datetime, timedelta
-        old_date = (datetime.now() - timedelta(days=3)).strftime('%Y-%m-%d %H:%M:%S')
+        from datetime import timedelta
+        from javdb.pipeline.policies import history_now
+        old_date = (history_now() - timedelta(days=3)).strftime('%Y-%m-%d %H:%M:%S')
         history_data = {
             '/v/ABC-123': {'last_visited_datetime': old_date, 'update_datetime': old_date, 'torrent_types': ['subtitle']}
         }
@@ -339,9 +343,9 @@ def test_recent_release_skip_returns_false_without_history(skip_fn):
 @pytest.mark.parametrize("skip_fn", _RECENT_RELEASE_SKIP_FUNCS)
 def test_recent_release_skip_respects_release_flag(skip_fn):
     """A recent visit should not skip when the release flag is disabled."""
-    from datetime import datetime
+    from javdb.pipeline.policies import history_now
 
-    today = datetime.now().strftime('%Y-%m-%d %H:%M:%S')
+    today = history_now().strftime('%Y-%m-%d %H:%M:%S')
     history_data = {
         '/v/ABC-123': {'last_visited_datetime': today, 'update_datetime': today, 'torrent_types': ['subtitle']}
     }
@@ -360,15 +364,41 @@ def test_recent_release_skip_ignores_empty_visit_timestamps(skip_fn):
 @pytest.mark.parametrize("skip_fn", _RECENT_RELEASE_SKIP_FUNCS)
 def test_recent_release_skip_falls_back_to_update_datetime(skip_fn):
     """Both recent-release helpers should fall back to update_datetime."""
-    from datetime import datetime
+    from javdb.pipeline.policies import history_now
 
-    today = datetime.now().strftime('%Y-%m-%d %H:%M:%S')
+    today = history_now().strftime('%Y-%m-%d %H:%M:%S')
     history_data = {
         '/v/ABC-123': {'last_visited_datetime': '', 'update_datetime': today, 'torrent_types': ['subtitle']}
     }
     assert skip_fn('/v/ABC-123', history_data, True) is True
 
 
+class TestHistoryTimezone:
+    """JAVDB_HISTORY_TZ handling shared with the Rust history manager."""
+
+    def test_unset_uses_utc(self, monkeypatch):
+        from datetime import timedelta
+        from javdb.pipeline.policies import history_now
+        monkeypatch.delenv('JAVDB_HISTORY_TZ', raising=False)
+        assert history_now().utcoffset() == timedelta(0)
+
+    def test_fixed_offset(self, monkeypatch):
+        from datetime import timedelta
+        from javdb.pipeline import policies
+        if policies._rust_parse_tz_offset is None:
+            pytest.skip("Rust core not installed")
+        monkeypatch.setenv('JAVDB_HISTORY_TZ', '+08:00')
+        assert policies.history_now().utcoffset() == timedelta(hours=8)
+        monkeypatch.setenv('JAVDB_HISTORY_TZ', 'UTC')
+        assert policies.history_now().utcoffset() == timedelta(0)
+
+    def test_invalid_value_falls_back_to_utc(self, monkeypatch):
+        from datetime import timedelta
+        from javdb.pipeline.policies import history_now
+        monkeypatch.setenv('JAVDB_HISTORY_TZ', 'Asia/Tokyo')
+        assert history_now().utcoffset() == timedelta(0)
+
+
 class TestShouldProcessMovie:
     """Test cases for should_process_movie function."""
     
@@ -744,6 +774,36 @@ class TestSaveAndLoadIntegration:
         assert 'subtitle' in result['/v/INT-001']['torrent_types']
 
 
+class TestSaveMatchesByVideoCode:
+    """A save under a new href takes over the row of the same title."""
+
+    @staticmethod
+    def _save_and_read(history_file, save):
+        from javdb.storage.history_manager import HISTORY_FIELDNAMES
+        with open(history_file, 'w', newline='', encoding='utf-8-sig') as f:
+            writer = csv.DictWriter(f, fieldnames=HISTORY_FIELDNAMES, restval='')
+            writer.writeheader()
+            writer.writerow({'href': '/v/old', 'phase': '1', 'video_code': 'abc00123',
+                             'no_subtitle': '[2026-01-01]magnet:?xt=urn:btih:old'})
+            writer.writerow({'href': '/v/other', 'phase': '1', 'video_code': 'ABC-1234'})
+        save(history_file, '/v/new', 1, 'ABC-123', {'subtitle': 'magnet:?xt=urn:btih:new'})
+        with open(history_file, 'r', encoding='utf-8-sig') as f:
+            rows = list(csv.DictReader(f))
+        return sorted((r['href'], r['video_code'], bool(r['subtitle'])) for r in rows)
+
+    def test_python_and_rust_writers_agree(self, temp_dir):
+        from javdb.storage.history_manager import _csv_save_parsed_movie_to_history
+        try:
+            from javdb.rust_core import save_parsed_movie_to_history as rust_save
+        except ImportError:
+            pytest.skip("Rust core not installed")
+        expected = [('/v/new', 'abc00123', True), ('/v/other', 'ABC-1234', False)]
+        python_rows = self._save_and_read(
+            os.path.join(temp_dir, 'python.csv'), _csv_save_parsed_movie_to_history)
+        assert python_rows == expected
+        assert self._save_and_read(os.path.join(temp_dir, 'rust.csv'), rust_save) == expected
+
+
 class TestBatchUpdateLastVisited:
     """Test cases for batch_update_last_visited function."""
 
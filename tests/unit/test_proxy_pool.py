@@ -635,6 +635,26 @@ class TestProxyPoolBanProxy:
         for _ in range(5):
             assert pool.get_next_proxy() != {"http": "http://revive1:8080"}
 
+    def test_pools_keep_their_own_ban_duration(self):
+        """Building a second pool must not change how long the first one bans."""
+        short = ProxyPool(ban_duration_days=1)
+        short.add_proxy(http_url="http://dur-short1:8080", name="dur-short-1")
+        short.add_proxy(http_url="http://dur-short2:8080", name="dur-short-2")
+        long = ProxyPool(ban_duration_days=5)
+        long.add_proxy(http_url="http://dur-long1:8080", name="dur-long-1")
+        long.add_proxy(http_url="http://dur-long2:8080", name="dur-long-2")
+
+        short.ban_proxy("dur-short-1")
+        long.ban_proxy("dur-long-1")
+
+        fmt = "%Y-%m-%d %H:%M:%S"
+        bans = {b['proxy_name']: b for b in short.ban_manager.get_banned_proxies()}
+        for name, days in (("dur-short-1", 1), ("dur-long-1", 5)):
+            ban = bans[name]
+            length = (datetime.strptime(ban['unban_time'], fmt)
+                      - datetime.strptime(ban['ban_time'], fmt))
+            assert length == timedelta(days=days)
+
 
 class TestProxyPoolBannedSelectionSkip:
     """Banned proxies must be excluded from selection.